@@ -0,0 +1,146 @@
+use crate::prelude::*;
+use crate::{Set,Permutation,ranking::kendall_distance,compat::sqrt};
+
+/// Agreement of the rankings about one item
+#[derive(Default,Clone,Copy,Debug)]
+pub struct Agreement {
+    /// mean rank
+    pub mean: f64,
+    /// standard deviation of the ranks
+    pub sd: f64,
+    /// best (lowest) rank
+    pub min: usize,
+    /// worst (highest) rank
+    pub max: usize
+}
+
+impl core::fmt::Display for Agreement {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f,"mean: {} sd: {} min: {} max: {}",self.mean,self.sd,self.min,self.max)
+    }
+}
+
+/// Rank vectors of sets ranking the same items (see `Set::rankvec`)
+pub fn rankvecs<T>(sets: &[&Set<T>]) -> Vec<Vec<usize>> where T: Copy+PartialOrd+Default {
+    sets.iter().map(|s| s.rankvec()).collect()
+}
+
+/// Checks that there are some rank vectors, all permutations of 0..n for the same n, and returns n
+fn itemscount<R: AsRef<[usize]>>(ranks: &[R]) -> usize {
+    assert!(!ranks.is_empty(), "aggregate: no rankings");
+    let n = ranks[0].as_ref().len();
+    assert!(ranks.iter().all(|r| r.as_ref().len() == n), "aggregate: rankings of different lengths");
+    for (i,r) in ranks.iter().enumerate() {
+        assert!(Permutation::new(r.as_ref().to_vec()).is_some(), "aggregate: ranking {i} is not a permutation of 0..{n}");
+    }
+    n
+}
+
+/// Borda count: each ranking awards n-1-rank points to each item.
+/// Returns the points, as a descending Ranked set (most points rank 0).
+pub fn borda<R: AsRef<[usize]>>(ranks: &[R]) -> Set<f64> {
+    let n = itemscount(ranks);
+    let mut points = vec![0_f64; n];
+    for r in ranks {
+        for (p,&rank) in points.iter_mut().zip(r.as_ref()) { *p += (n-1-rank) as f64 }
+    }
+    Set::new_ranked(&points, false)
+}
+
+/// Mean rank of each item, as an ascending Ranked set
+pub fn mean_rank<R: AsRef<[usize]>>(ranks: &[R]) -> Set<f64> {
+    let means: Vec<f64> = agreement(ranks).iter().map(|a| a.mean).collect();
+    Set::new_ranked(&means, true)
+}
+
+/// Median rank of each item, as an ascending Ranked set
+pub fn median_rank<R: AsRef<[usize]>>(ranks: &[R]) -> Set<f64> {
+    let n = itemscount(ranks);
+    let m = ranks.len();
+    let medians: Vec<f64> = (0..n).map(|item| {
+        let mut itemranks: Vec<usize> = ranks.iter().map(|r| r.as_ref()[item]).collect();
+        itemranks.sort_unstable();
+        if m % 2 == 1 { itemranks[m/2] as f64 }
+        else { (itemranks[m/2-1] + itemranks[m/2]) as f64 / 2.0 }
+    }).collect();
+    Set::new_ranked(&medians, true)
+}
+
+/// Kemeny score of a candidate rank vector:
+/// the sum of its Kendall tau distances to all the rankings (lower is better)
+pub fn kemeny_score<R: AsRef<[usize]>>(ranks: &[R], candidate: &[usize]) -> u64 {
+    let n = itemscount(ranks);
+    assert!(candidate.len() == n && Permutation::new(candidate.to_vec()).is_some(),
+        "aggregate: candidate is not a permutation of 0..{n}");
+    ranks.iter().map(|r| kendall_distance(r.as_ref(), candidate)).sum()
+}
+
+/// Approximately Kemeny optimal ranking: starts from the Borda ranking
+/// and improves it by local search, moving single items to their best positions,
+/// as judged by the pairwise preferences, until no move reduces the Kemeny score.
+/// Needs n^2 memory for the preferences. Returns the consensus ranks as an ascending Ranked set.
+pub fn kemeny<R: AsRef<[usize]>>(ranks: &[R]) -> Set<f64> {
+    let n = itemscount(ranks);
+    // prefs[a*n+b] = number of rankings placing item a before item b
+    let mut prefs = vec![0_u32; n*n];
+    for r in ranks {
+        let r = r.as_ref();
+        for a in 0..n { for b in 0..n { if r[a] < r[b] { prefs[a*n+b] += 1 } } }
+    }
+    let mut order = borda(ranks).index.iter().enumerate()
+        .fold(vec![0_usize; n], |mut ord,(item,&rank)| { ord[rank] = item; ord });
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..n {
+            let item = order[i];
+            // cost change of moving item from position i to each position j
+            let (mut best, mut bestj) = (0_i64, i);
+            let mut delta = 0_i64;
+            for j in (0..i).rev() { // moving it ahead of order[j]
+                let other = order[j];
+                delta += prefs[other*n+item] as i64 - prefs[item*n+other] as i64;
+                if delta < best { best = delta; bestj = j };
+            }
+            delta = 0;
+            for (j,&other) in order.iter().enumerate().skip(i+1) { // moving it behind order[j]
+                delta += prefs[item*n+other] as i64 - prefs[other*n+item] as i64;
+                if delta < best { best = delta; bestj = j };
+            }
+            if bestj != i {
+                order.remove(i);
+                order.insert(bestj, item);
+                improved = true;
+            }
+        }
+    }
+    let mut consensus = vec![0_f64; n];
+    for (rank,&item) in order.iter().enumerate() { consensus[item] = rank as f64 };
+    Set::new_ranked(&consensus, true)
+}
+
+/// Per item agreement statistics of the rankings
+pub fn agreement<R: AsRef<[usize]>>(ranks: &[R]) -> Vec<Agreement> {
+    let n = itemscount(ranks);
+    let m = ranks.len() as f64;
+    (0..n).map(|item| {
+        let (mut sum, mut sumsq, mut min, mut max) = (0_f64, 0_f64, usize::MAX, 0_usize);
+        for r in ranks {
+            let rank = r.as_ref()[item];
+            sum += rank as f64; sumsq += (rank*rank) as f64;
+            min = min.min(rank); max = max.max(rank);
+        }
+        let mean = sum/m;
+        Agreement{ mean, sd: sqrt((sumsq/m - mean*mean).max(0.0)), min, max }
+    }).collect()
+}
+
+/// Kendall's W, coefficient of concordance of the rankings (without ties):
+/// 1 for complete agreement, 0 for none
+pub fn concordance<R: AsRef<[usize]>>(ranks: &[R]) -> f64 {
+    let n = itemscount(ranks) as f64;
+    let m = ranks.len() as f64;
+    let mean = m*(n-1.0)/2.0;
+    let s: f64 = agreement(ranks).iter().map(|a| { let d = a.mean*m - mean; d*d }).sum();
+    12.0*s/(m*m*(n*n*n-n))
+}
@@ -0,0 +1,70 @@
+use crate::prelude::*;
+use crate::{SType,Set,Permutation};
+use core::mem::size_of;
+
+/// Heap bytes allocated by a Set, see `Set::memory_usage`
+#[derive(Default,Clone,Copy,Debug,PartialEq,Eq)]
+pub struct MemoryUsage {
+    /// bytes allocated for data
+    pub data: usize,
+    /// bytes allocated for the index
+    pub index: usize,
+    /// bytes of the data actually in use
+    pub data_used: usize,
+    /// bytes of the index actually in use
+    pub index_used: usize
+}
+
+impl MemoryUsage {
+    /// Total heap bytes allocated
+    pub fn total(&self) -> usize { self.data + self.index }
+}
+
+impl core::fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "data: {} of {} bytes used, index: {} of {} bytes used",
+            self.data_used, self.data, self.index_used, self.index)
+    }
+}
+
+/// Capacity management, for long-lived sets growing and shrinking
+/// through `minsert` and `mdelete`
+impl<T> Set<T> where T: Copy+PartialOrd+Default {
+
+    /// Set of set_type and asc order with no data yet,
+    /// but with room for n items (and their index, when Indexed or Ranked).
+    /// It is filled by `minsert`.
+    pub fn with_capacity(set_type: SType, asc: bool, n: usize) -> Self {
+        let indexed = matches!(set_type, SType::Indexed | SType::Ranked);
+        Set{ stype:set_type, ascending:asc, data:Vec::with_capacity(n),
+            index:Permutation(Vec::with_capacity(if indexed { n } else { 0 })) }
+    }
+
+    /// Reserves room for at least additional more items (and their index)
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+        if let SType::Indexed | SType::Ranked = self.stype { self.index.0.reserve(additional) };
+    }
+
+    /// Releases the unused capacity of data and index
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+        self.index.0.shrink_to_fit();
+    }
+
+    /// Deletes all the items, keeping the allocations, the SType and the order,
+    /// as for a set made `with_capacity`, which `minsert` refills in kind.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.index.0.clear();
+    }
+
+    /// Heap bytes allocated and used by data and index
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage{
+            data: self.data.capacity()*size_of::<T>(),
+            index: self.index.0.capacity()*size_of::<usize>(),
+            data_used: self.data.len()*size_of::<T>(),
+            index_used: self.index.len()*size_of::<usize>() }
+    }
+}
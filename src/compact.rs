@@ -0,0 +1,198 @@
+use crate::prelude::*;
+use crate::{SType,Set,SetView,Permutation};
+use crate::compat::Vecops;
+use alloc::borrow::Cow;
+use core::fmt;
+
+/// Unsigned integer types usable as compact indices of sets
+pub trait IndexInt: Copy+Default+Eq+fmt::Debug {
+    /// Largest representable index value
+    const MAX: usize;
+    /// Conversion from usize, which must not exceed MAX
+    fn from_usize(i: usize) -> Self;
+    /// Conversion to usize
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! index_int {
+    ($($t:ty),*) => { $( impl IndexInt for $t {
+        const MAX: usize = <$t>::MAX as usize;
+        fn from_usize(i: usize) -> Self { i as $t }
+        fn to_usize(self) -> usize { self as usize } } )* }
+}
+index_int!(u16,u32,usize);
+
+/// Error of a set growing longer than its index type can address
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct IndexOverflow {
+    /// number of items required
+    pub len: usize,
+    /// largest index value of the index type
+    pub max: usize
+}
+
+impl fmt::Display for IndexOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "index overflow: {} items need index values up to {}, the index type holds at most {}",
+            self.len, self.len-1, self.max)
+    }
+}
+
+impl core::error::Error for IndexOverflow {}
+
+/// Set with its index stored as I (u32 or u16), rather than usize,
+/// to save memory on large Indexed and Ranked sets
+#[derive(Default,Clone)]
+pub struct CompactSet<T,I> {
+    /// type of the set
+    pub stype: SType,
+    /// order: ascending (true), descending (false)
+    pub ascending: bool,
+    /// data Vec
+    pub data: Vec<T>,
+    /// index: sort index of Indexed sets, ranks of Ranked sets, empty otherwise
+    pub index: Vec<I>
+}
+
+/// Checks that len items are addressable by I
+fn check<I: IndexInt>(len: usize) -> Result<(),IndexOverflow> {
+    if len > 0 && len-1 > I::MAX { Err(IndexOverflow{ len, max:I::MAX }) } else { Ok(()) }
+}
+
+/// Inverse of permutation p: sort index <-> ranks
+fn invert<I: IndexInt>(p: &[I]) -> Vec<I> {
+    let mut inv = vec![I::default(); p.len()];
+    for (i,&j) in p.iter().enumerate() { inv[j.to_usize()] = I::from_usize(i) };
+    inv
+}
+
+/// Narrows the index of s, or reports IndexOverflow
+impl<T,I: IndexInt> TryFrom<Set<T>> for CompactSet<T,I> {
+    type Error = IndexOverflow;
+    fn try_from(s: Set<T>) -> Result<Self,IndexOverflow> {
+        check::<I>(s.data.len())?;
+        Ok(CompactSet{ stype:s.stype, ascending:s.ascending, data:s.data,
+            index:s.index.iter().map(|&i| I::from_usize(i)).collect() })
+    }
+}
+
+/// Widens the index back to usize
+impl<T,I: IndexInt> From<CompactSet<T,I>> for Set<T> {
+    fn from(s: CompactSet<T,I>) -> Self {
+        Set{ stype:s.stype, ascending:s.ascending, data:s.data,
+            index:Permutation(s.index.iter().map(|&i| i.to_usize()).collect()) }
+    }
+}
+
+impl<T,I> CompactSet<T,I> where T: Copy+PartialOrd+Default, I: IndexInt {
+
+    /// All in one Initialiser, as `Set::new`, reporting data too long for I
+    pub fn new(set_type: SType, d: &[T], asc: bool) -> Result<Self,IndexOverflow> {
+        check::<I>(d.len())?;
+        Self::try_from(Set::new(set_type, d, asc))
+    }
+
+    /// Copy as a Set, with usize index
+    pub fn to_set(&self) -> Set<T> { self.clone().into() }
+
+    /// Number of items
+    pub fn len(&self) -> usize { self.data.len() }
+
+    /// True when the set has no items
+    pub fn is_empty(&self) -> bool { self.data.is_empty() }
+
+    /// Subscripts of data in the sort order of Indexed and Ranked sets
+    fn sortindex(&self) -> Cow<'_,[I]> {
+        match self.stype {
+            SType::Ranked => Cow::Owned(invert(&self.index)),
+            _ => Cow::Borrowed(&self.index)
+        }
+    }
+
+    /// Insertion position of m in sortindex, before any equal items
+    fn position(&self, sortindex: &[I], m: &T) -> usize {
+        if self.ascending { sortindex.partition_point(|&i| self.data[i.to_usize()] < *m) }
+        else { sortindex.partition_point(|&i| self.data[i.to_usize()] > *m) }
+    }
+
+    /// Converts to indexed, as `Set::to_indexed`.
+    /// Indexed and Ranked sets are converted without widening their index.
+    pub fn to_indexed(&self, asc: bool) -> Self {
+        let index = match self.stype {
+            SType::Indexed | SType::Ranked => self.sortindex().into_owned(),
+            _ => return Self::try_from(self.to_set().to_indexed(asc)).expect("CompactSet::to_indexed: same length")
+        };
+        CompactSet{ stype:SType::Indexed, ascending:asc, data:self.data.clone(),
+            index: if self.ascending == asc { index } else { index.revs() } }
+    }
+
+    /// Converts to ranked, as `Set::to_ranked`.
+    /// Indexed and Ranked sets are converted without widening their index.
+    pub fn to_ranked(&self, asc: bool) -> Self {
+        match self.stype {
+            SType::Indexed | SType::Ranked => {
+                let indexed = self.to_indexed(asc);
+                CompactSet{ stype:SType::Ranked, ascending:asc, data:indexed.data, index:invert(&indexed.index) } },
+            _ => Self::try_from(self.to_set().to_ranked(asc)).expect("CompactSet::to_ranked: same length")
+        }
+    }
+
+    /// Search for m.
+    /// Returns the subscript of the first m or None
+    pub fn search(&self, m: T) -> Option<usize> {
+        match self.stype {
+            SType::Indexed | SType::Ranked => {
+                let sortindex = self.sortindex();
+                let pos = self.position(&sortindex, &m);
+                sortindex.get(pos).map(|&i| i.to_usize()).filter(|&i| self.data[i] == m) },
+            _ => SetView{ stype:self.stype, ascending:self.ascending, data:&self.data, index:Cow::Borrowed(&[]) }.search(m)
+        }
+    }
+
+    /// True if m is a member of the set
+    pub fn member(&self, m: T) -> bool { self.search(m).is_some() }
+
+    /// Inserts item, as `MutSetOps::minsert`,
+    /// or reports IndexOverflow, leaving self unchanged
+    pub fn minsert(&mut self, item: T) -> Result<(),IndexOverflow> {
+        check::<I>(self.data.len()+1)?;
+        match self.stype {
+            SType::Indexed => {
+                let pos = self.position(&self.index, &item);
+                self.data.push(item);
+                self.index.insert(pos, I::from_usize(self.data.len()-1)); },
+            SType::Ranked => {
+                let pos = self.position(&self.sortindex(), &item);
+                self.data.push(item);
+                for rank in &mut self.index {
+                    if rank.to_usize() >= pos { *rank = I::from_usize(rank.to_usize()+1) } };
+                self.index.push(I::from_usize(pos)); },
+            SType::Empty => { self.stype = SType::Ordered; self.data.push(item); },
+            SType::Unordered => self.data.push(item),
+            SType::Ordered => {
+                let pos = if self.ascending { self.data.partition_point(|x| *x < item) }
+                    else { self.data.partition_point(|x| *x > item) };
+                self.data.insert(pos, item); }
+        };
+        Ok(())
+    }
+
+    /// Deletes the first matching item, as `MutSetOps::mdelete`.
+    /// Returns false when not found.
+    pub fn mdelete(&mut self, item: T) -> bool {
+        let Some(datasub) = self.search(item) else { return false };
+        match self.stype {
+            SType::Unordered => { self.data.swap_remove(datasub); },
+            SType::Empty | SType::Ordered => { self.data.remove(datasub); },
+            SType::Indexed | SType::Ranked => {
+                self.data.remove(datasub);
+                let ranked = matches!(self.stype, SType::Ranked);
+                let mut sortindex = self.sortindex().into_owned();
+                sortindex.retain(|&i| i.to_usize() != datasub);
+                for i in &mut sortindex { // repair the subscripts after datasub
+                    if i.to_usize() > datasub { *i = I::from_usize(i.to_usize()-1) } };
+                self.index = if ranked { invert(&sortindex) } else { sortindex }; }
+        };
+        true
+    }
+}
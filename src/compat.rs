@@ -0,0 +1,239 @@
+//! The indxvec traits used by this crate. With the `std` feature, they are indxvec's own.
+//! Without it, as indxvec needs std, the subset used here is reimplemented over `alloc`.
+//! The f64 functions that need std are taken from `libm` without it.
+
+#[cfg(feature = "std")]
+pub use indxvec::{Indices,MinMax,Mutops,Printing,Vecops};
+
+#[cfg(not(feature = "std"))]
+pub use nostd::{Indices,MinMax,Mutops,Printing,Vecops};
+
+#[cfg(feature = "std")]
+pub use std_float::{abs,cbrt,exp,sqrt};
+
+#[cfg(not(feature = "std"))]
+pub use libm::{fabs as abs,cbrt,exp,sqrt};
+
+/// The f64 methods of std, as functions
+#[cfg(feature = "std")]
+mod std_float {
+    pub fn abs(x: f64) -> f64 { x.abs() }
+    pub fn cbrt(x: f64) -> f64 { x.cbrt() }
+    pub fn exp(x: f64) -> f64 { x.exp() }
+    pub fn sqrt(x: f64) -> f64 { x.sqrt() }
+}
+
+/// The replacements of the indxvec traits, compiled also with std,
+/// so that they are tested against indxvec itself
+pub mod nostd {
+    use alloc::{vec,vec::Vec,string::{String,ToString},format};
+    use core::{cmp::Ordering,fmt,ops::Range};
+
+    /// Minimum value, its first index, maximum value, its first index (as in indxvec)
+    #[derive(Default)]
+    pub struct MinMax<T> {
+        /// Minimum value
+        pub min: T,
+        /// Subscript (index) of the minimum
+        pub minindex: usize,
+        /// Maximum value
+        pub max: T,
+        /// Subscript (index) of the maximum
+        pub maxindex: usize
+    }
+
+    impl<T: fmt::Display> fmt::Display for MinMax<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "min: {GR}{}{UN}, minindex: {YL}{}{UN}, max: {GR}{}{UN}, maxindex: {YL}{}{UN}",
+                self.min, self.minindex, self.max, self.maxindex)
+        }
+    }
+
+    const GR: &str = "\x1B[1;32m";
+    const YL: &str = "\x1B[1;33m";
+    const UN: &str = "\x1B[0m";
+
+    /// Comparison that panics on incomparable items, as the indxvec sorts do
+    fn cmp<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+        a.partial_cmp(b).expect("sort comparison failure")
+    }
+
+    /// Operations on slices, named and behaving as in indxvec
+    pub trait Vecops<T> {
+        /// Range of the items equal to target, in ascending or descending sorted self
+        fn binsearch(self, target: &T) -> Range<usize> where T: PartialOrd;
+        /// Range of the items equal to target, in self sorted by idx
+        fn binsearch_indexed(self, idx: &[usize], target: &T) -> Range<usize> where T: PartialOrd;
+        /// First (forward) or last subscript of m
+        fn member(self, m: T, forward: bool) -> Option<usize> where T: PartialEq+Clone;
+        /// Minimum, its first index, maximum, its first index
+        fn minmax(self) -> MinMax<T> where T: PartialOrd+Clone;
+        /// Reversed copy
+        fn revs(self) -> Vec<T> where T: Clone;
+        /// Sorted copy, ascending or descending
+        fn sortm(self, ascending: bool) -> Vec<T> where T: PartialOrd+Clone;
+        /// Stable ascending sort index
+        fn mergesort_indexed(self) -> Vec<usize> where T: PartialOrd+Clone;
+        /// Ascending sort index by quantify
+        fn hashsort_indexed(self, quantify: impl Copy+Fn(&T) -> f64) -> Vec<usize> where T: PartialOrd+Clone;
+        /// Merge of two ascending slices, keeping all the items
+        fn merge(self, v2: &[T]) -> Vec<T> where T: PartialOrd+Clone;
+        /// Intersection of two ascending slices
+        fn intersect(self, v2: &[T]) -> Vec<T> where T: PartialOrd+Clone;
+        /// Items of ascending self not matched in ascending v2
+        fn diff(self, v2: &[T]) -> Vec<T> where T: PartialOrd+Clone;
+    }
+
+    /// Sort index of d[i..i+n], by the merge sort of indxvec,
+    /// which takes equal items alternately from the two halves
+    fn mergesortslice<T: PartialOrd>(d: &[T], i: usize, n: usize) -> Vec<usize> {
+        match n {
+            1 => return vec![i],
+            2 => return if d[i+1] < d[i] { vec![i+1, i] } else { vec![i, i+1] },
+            _ => ()
+        };
+        let (idx1, idx2) = (mergesortslice(d, i, n/2), mergesortslice(d, i + n/2, n - n/2));
+        let mut res = Vec::with_capacity(n);
+        let (mut i1, mut i2) = (0, 0);
+        while i1 < idx1.len() && i2 < idx2.len() {
+            match cmp(&d[idx1[i1]], &d[idx2[i2]]) {
+                Ordering::Less => { res.push(idx1[i1]); i1 += 1 },
+                Ordering::Greater => { res.push(idx2[i2]); i2 += 1 },
+                Ordering::Equal => {
+                    res.push(idx1[i1]); i1 += 1;
+                    if i1 < idx1.len() { res.push(idx2[i2]); i2 += 1 } }
+            }
+        }
+        res.extend_from_slice(&idx1[i1..]);
+        res.extend_from_slice(&idx2[i2..]);
+        res
+    }
+
+    impl<T> Vecops<T> for &[T] {
+        fn binsearch(self, target: &T) -> Range<usize> where T: PartialOrd {
+            let desc = *self.last().expect("binsearch: no data") < self[0];
+            let start = self.partition_point(|x| if desc { x > target } else { x < target });
+            let end = self.partition_point(|x| if desc { x >= target } else { x <= target });
+            start..end
+        }
+        fn binsearch_indexed(self, idx: &[usize], target: &T) -> Range<usize> where T: PartialOrd {
+            let desc = self[idx[idx.len()-1]] < self[idx[0]];
+            let start = idx.partition_point(|&i| if desc { self[i] > *target } else { self[i] < *target });
+            let end = idx.partition_point(|&i| if desc { self[i] >= *target } else { self[i] <= *target });
+            start..end
+        }
+        fn member(self, m: T, forward: bool) -> Option<usize> where T: PartialEq+Clone {
+            if forward { self.iter().position(|x| *x == m) } else { self.iter().rposition(|x| *x == m) }
+        }
+        fn minmax(self) -> MinMax<T> where T: PartialOrd+Clone {
+            let (mut minindex, mut maxindex) = (0, 0);
+            for (i,x) in self.iter().enumerate().skip(1) {
+                if *x < self[minindex] { minindex = i } else if *x > self[maxindex] { maxindex = i }
+            }
+            MinMax{ min:self[minindex].clone(), minindex, max:self[maxindex].clone(), maxindex }
+        }
+        fn revs(self) -> Vec<T> where T: Clone { self.iter().rev().cloned().collect() }
+        fn sortm(self, ascending: bool) -> Vec<T> where T: PartialOrd+Clone {
+            let mut sorted = self.to_vec();
+            sorted.sort_by(cmp);
+            if !ascending { sorted.reverse() };
+            sorted
+        }
+        fn mergesort_indexed(self) -> Vec<usize> where T: PartialOrd+Clone {
+            if self.is_empty() { return Vec::new() };
+            mergesortslice(self, 0, self.len())
+        }
+        fn hashsort_indexed(self, quantify: impl Copy+Fn(&T) -> f64) -> Vec<usize> where T: PartialOrd+Clone {
+            let keys: Vec<f64> = self.iter().map(quantify).collect();
+            let mut idx: Vec<usize> = (0..self.len()).collect();
+            idx.sort_by(|&a,&b| cmp(&keys[a],&keys[b]));
+            idx
+        }
+        fn merge(self, v2: &[T]) -> Vec<T> where T: PartialOrd+Clone {
+            let mut res = Vec::with_capacity(self.len()+v2.len());
+            let (mut i, mut j) = (0, 0);
+            while i < self.len() && j < v2.len() {
+                if self[i] < v2[j] { res.push(self[i].clone()); i += 1 } else { res.push(v2[j].clone()); j += 1 }
+            }
+            res.extend_from_slice(&self[i..]);
+            res.extend_from_slice(&v2[j..]);
+            res
+        }
+        fn intersect(self, v2: &[T]) -> Vec<T> where T: PartialOrd+Clone {
+            let mut res = Vec::new();
+            let (mut i, mut j) = (0, 0);
+            while i < self.len() && j < v2.len() {
+                if self[i] < v2[j] { i += 1 } else if self[i] > v2[j] { j += 1 }
+                else { res.push(self[i].clone()); i += 1; j += 1 }
+            }
+            res
+        }
+        fn diff(self, v2: &[T]) -> Vec<T> where T: PartialOrd+Clone {
+            let mut res = Vec::new();
+            let (mut i, mut j) = (0, 0);
+            while i < self.len() {
+                if j == v2.len() || self[i] < v2[j] { res.push(self[i].clone()); i += 1 }
+                else if self[i] > v2[j] { j += 1 }
+                else { i += 1; j += 1 }
+            }
+            res
+        }
+    }
+
+    /// Operations on index slices, named and behaving as in indxvec
+    pub trait Indices {
+        /// Inverse permutation: sort index <-> ranks
+        fn invindex(self) -> Vec<usize>;
+        /// Complement of ranks, toggling ascending/descending
+        fn complindex(self) -> Vec<usize>;
+        /// Items of v in the order of self, or in its reverse
+        fn unindex<T: Clone>(self, v: &[T], ascending: bool) -> Vec<T>;
+    }
+
+    impl Indices for &[usize] {
+        fn invindex(self) -> Vec<usize> {
+            let mut index = vec![0; self.len()];
+            for (i,&pos) in self.iter().enumerate() { index[pos] = i };
+            index
+        }
+        fn complindex(self) -> Vec<usize> {
+            let n = self.len();
+            self.iter().map(|&i| n-i-1).collect()
+        }
+        fn unindex<T: Clone>(self, v: &[T], ascending: bool) -> Vec<T> {
+            if ascending { self.iter().map(|&i| v[i].clone()).collect() }
+            else { self.iter().rev().map(|&i| v[i].clone()).collect() }
+        }
+    }
+
+    /// In place operations, named as in indxvec
+    pub trait Mutops<T> {
+        /// Reverses in place
+        fn mutrevs(self);
+    }
+
+    impl<T> Mutops<T> for &mut [T] {
+        fn mutrevs(self) { self.reverse() }
+    }
+
+    /// Coloured printing, named as in indxvec
+    pub trait Printing<T>: Sized {
+        /// Plain string, with brackets for slices
+        fn to_str(self) -> String;
+        /// Printable in green
+        fn gr(self) -> String { format!("{GR}{}{UN}", self.to_str()) }
+        /// Printable in yellow
+        fn yl(self) -> String { format!("{YL}{}{UN}", self.to_str()) }
+    }
+
+    impl<T: fmt::Display> Printing<T> for &T {
+        fn to_str(self) -> String { self.to_string() }
+    }
+
+    impl<T: fmt::Display> Printing<T> for &[T] {
+        fn to_str(self) -> String {
+            let items: Vec<String> = self.iter().map(|x| x.to_string()).collect();
+            format!("[{}]", items.join(" "))
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::prelude::*;
+use crate::{SType,Set};
+use crate::compat::{Indices,Vecops};
+use alloc::borrow::Cow;
+
+/// Size ratio of the operands, above which intersection gallops
+/// through the larger one, instead of merging them linearly
+pub const GALLOP_RATIO: usize = 8;
+
+/// Ascending view of the items of any Set, copying only Unordered data
+pub(crate) struct AscView<'a,T:Clone> {
+    data: Cow<'a,[T]>,
+    idx: Option<Cow<'a,[usize]>>,
+    rev: bool
+}
+
+impl<'a,T> AscView<'a,T> where T: Copy+PartialOrd+Default {
+
+    /// View of s: Ordered and Indexed sets are viewed in place,
+    /// Ranked sets through their inverted ranks, Unordered sets are sorted
+    pub(crate) fn new(s: &'a Set<T>) -> Self { Self::of(s.stype, s.ascending, &s.data, &s.index) }
+
+    /// View of the parts of a Set (or of a `SetView`), as `new`
+    pub(crate) fn of(stype: SType, asc: bool, data: &'a [T], index: &'a [usize]) -> Self {
+        match stype {
+            SType::Empty => AscView{ data:Cow::Owned(Vec::new()), idx:None, rev:false },
+            SType::Unordered => AscView{ data:Cow::Owned(data.sortm(true)), idx:None, rev:false },
+            SType::Ordered => AscView{ data:Cow::Borrowed(data), idx:None, rev:!asc },
+            SType::Indexed => AscView{ data:Cow::Borrowed(data), idx:Some(Cow::Borrowed(index)), rev:!asc },
+            SType::Ranked => AscView{ data:Cow::Borrowed(data), idx:Some(Cow::Owned(index.invindex())), rev:!asc }
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize { self.data.len() }
+
+    /// The items as one ascending slice, when they are stored so
+    pub(crate) fn as_slice(&self) -> Option<&[T]> {
+        if self.idx.is_none() && !self.rev { Some(&self.data) } else { None }
+    }
+
+    /// i-th smallest item
+    pub(crate) fn get(&self, i: usize) -> T {
+        let j = if self.rev { self.data.len()-1-i } else { i };
+        match &self.idx {
+            Some(idx) => self.data[idx[j]],
+            None => self.data[j]
+        }
+    }
+
+    /// First position at or after from, whose item is not less than item,
+    /// found by exponential (galloping) and then binary search
+    pub(crate) fn gallop(&self, from: usize, item: &T) -> usize {
+        let n = self.len();
+        let mut step = 1;
+        let mut lo = from;
+        let mut hi = from;
+        while hi < n && self.get(hi) < *item { lo = hi + 1; hi = from + step; step *= 2; }
+        let mut hi = hi.min(n);
+        while lo < hi {
+            let mid = lo + (hi-lo)/2;
+            if self.get(mid) < *item { lo = mid + 1 } else { hi = mid }
+        }
+        lo
+    }
+}
+
+/// Ascending intersection of the items of s1 and s2, each matching pair yielding one item.
+/// Gallops through the larger operand when their sizes differ by more than `GALLOP_RATIO`,
+/// otherwise merges them.
+pub(crate) fn intersect<T>(s1: &Set<T>, s2: &Set<T>) -> Vec<T> where T: Copy+PartialOrd+Default {
+    intersect_views(&AscView::new(s1), &AscView::new(s2))
+}
+
+/// Ascending intersection of two views, as `intersect`
+pub(crate) fn intersect_views<T>(v1: &AscView<T>, v2: &AscView<T>) -> Vec<T> where T: Copy+PartialOrd+Default {
+    let (small, large) = if v1.len() <= v2.len() { (v1,v2) } else { (v2,v1) };
+    let mut res = Vec::new();
+    if large.len() > GALLOP_RATIO*small.len() {
+        let mut pos = 0;
+        for i in 0..small.len() {
+            let item = small.get(i);
+            pos = large.gallop(pos, &item);
+            if pos == large.len() { break };
+            if large.get(pos) == item { res.push(item); pos += 1; }
+        }
+    } else {
+        let (mut i, mut j) = (0, 0);
+        while i < v1.len() && j < v2.len() {
+            let (a, b) = (v1.get(i), v2.get(j));
+            if a < b { i += 1 } else if b < a { j += 1 } else { res.push(a); i += 1; j += 1 }
+        }
+    }
+    res
+}
+
+/// Ascending merge of all the items of two views, as indxvec `merge`,
+/// copying the runs of the larger one between the items of the smaller one, found by galloping
+pub(crate) fn union_views<T>(v1: &AscView<T>, v2: &AscView<T>) -> Vec<T> where T: Copy+PartialOrd+Default {
+    let mut res = Vec::with_capacity(v1.len() + v2.len());
+    let (small, large) = if v1.len() <= v2.len() { (v1,v2) } else { (v2,v1) };
+    if large.len() > GALLOP_RATIO*small.len() { // copies the runs of large between the items of small
+        let mut pos = 0;
+        for i in 0..small.len() {
+            let item = small.get(i);
+            let next = large.gallop(pos, &item);
+            res.extend((pos..next).map(|j| large.get(j)));
+            res.push(item);
+            pos = next;
+        }
+        res.extend((pos..large.len()).map(|j| large.get(j)));
+        return res;
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < v1.len() && j < v2.len() {
+        let (a, b) = (v1.get(i), v2.get(j));
+        if b < a { res.push(b); j += 1 } else { res.push(a); i += 1 }
+    }
+    res.extend((i..v1.len()).map(|i| v1.get(i)));
+    res.extend((j..v2.len()).map(|j| v2.get(j)));
+    res
+}
+
+/// Ascending items of v1 not matched by items of v2, as indxvec `diff`
+pub(crate) fn difference_views<T>(v1: &AscView<T>, v2: &AscView<T>) -> Vec<T> where T: Copy+PartialOrd+Default {
+    let mut res = Vec::with_capacity(v1.len());
+    let mut j = 0;
+    for i in 0..v1.len() {
+        let a = v1.get(i);
+        j = v2.gallop(j, &a);
+        if j < v2.len() && v2.get(j) == a { j += 1 } else { res.push(a) };
+    }
+    res
+}
+
+/// Subset tests, galloping through the larger operand, as `intersection`
+impl<T> Set<T> where T: Copy+PartialOrd+Default {
+
+    /// True when every item of self is a member of s (repeats disregarded)
+    pub fn is_subset(&self, s: &Self) -> bool {
+        let (sub, sup) = (AscView::new(self), AscView::new(s));
+        let mut pos = 0;
+        for i in 0..sub.len() {
+            let item = sub.get(i);
+            pos = sup.gallop(pos, &item);
+            if pos == sup.len() || sup.get(pos) != item { return false };
+        }
+        true
+    }
+
+    /// True when every item of s is a member of self (repeats disregarded)
+    pub fn is_superset(&self, s: &Self) -> bool { s.is_subset(self) }
+
+    /// True when self and s have no items in common
+    pub fn is_disjoint(&self, s: &Self) -> bool { intersect(self, s).is_empty() }
+}
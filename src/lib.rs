@@ -1,12 +1,68 @@
 #![warn(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
 //! Operations on  Unordered Sets, Ordered Sets, Indexed Sets, Ranked Sets
 
+extern crate alloc;
+
+/// alloc items, which are not in the prelude without std
+mod prelude {
+    pub use alloc::{vec,vec::Vec};
+}
+/// The indxvec traits, or their alloc only replacements without std
+mod compat;
+/// The alloc only replacements of the indxvec traits, for testing them against indxvec
+#[doc(hidden)]
+pub use compat::nostd;
+
 /// Associated functions for struct Set
 pub mod setimpls;
 /// Mutable set operations, implemented for &mut Set
 pub mod mutimpls;
+/// Tie aware ranks, rank correlations and distances
+pub mod ranking;
+/// Rank tests: Mann-Whitney U, Wilcoxon signed-rank, Kruskal-Wallis
+pub mod ranktests;
+/// Aggregation of several rankings of the same items
+pub mod aggregate;
+/// Rank-biased overlap and top-k comparisons of ranked lists
+pub mod topk;
+/// Validated permutations, the index type of Set
+pub mod permutation;
+/// Radix sort construction of sets of primitive numeric types
+pub mod radix;
+/// Pluggable sorting strategies for the constructors and converters
+pub mod strategy;
+/// Sets ordered by keys of their items, rather than by their PartialOrd
+pub mod setby;
+/// Multi-column tables, with companion rows following a Set of keys
+pub mod table;
+/// MinHash sketches and LSH index for near-duplicate set search
+pub mod minhash;
+/// Galloping intersection and subset tests for operands of skewed sizes
+pub mod gallop;
+/// SIMD kernels of intersection, union and difference of sorted u32 and u64 slices
+pub mod simd;
+/// Read-only sets over borrowed data
+pub mod view;
+/// Sets with compact (u32 or u16) indices
+pub mod compact;
+/// Capacity and memory management of sets
+pub mod capacity;
+/// Typed sets: UnorderedSet, OrderedSet, IndexedSet, RankedSet
+pub mod typed;
+/// Common SetLike interface of Set and the std sets, with conversions
+pub mod setlike;
+/// Set literals: the `set!` macro
+pub mod literal;
+/// Parsing of sets from text
+pub mod parse;
 
-use indxvec::{Printing};
+use crate::prelude::*;
+use crate::compat::{Printing,Vecops};
+pub use crate::compat::MinMax;
+pub use crate::permutation::Permutation;
+pub use crate::view::SetView;
+pub use crate::parse::ParseSetError;
 
 /// Constructs a trivial index (for already sorted sets), 
 /// of required ascending or descending order and size
@@ -37,8 +93,8 @@ pub enum SType {
 }
 
 /// Implementation of Display trait for struct Set.
-impl std::fmt::Display for SType {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for SType {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         writeln!(f,"{}",self.to_str())
         }
 }
@@ -52,13 +108,13 @@ pub struct Set<T> {
     pub ascending: bool,
     /// data Vec
     pub data: Vec<T>,
-    /// index Vec
-    pub index: Vec<usize>
+    /// index: sort index of Indexed sets, ranks of Ranked sets, empty otherwise
+    pub index: Permutation
 }
 
 /// Implementation of Display trait for struct Set.
-impl<T: std::fmt::Display> std::fmt::Display for Set<T> where T:Copy {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl<T: core::fmt::Display> core::fmt::Display for Set<T> where T:Copy {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match &self.stype {
             SType::Empty =>  writeln!(f,"Empty Set"),
             SType::Unordered => writeln!(f, "Unordered Set\nData: {}",self.data.gr()),
@@ -84,18 +140,49 @@ impl<T> Clone for Set<T> where T:Copy+PartialOrd+Default {
 }
 */
 
+/// Sorting of the data of unordered sets by their quantities, used by `mordered_by` and `mindexed_by`
+pub trait Quantify<T> {
+    /// Ascending sort index of d
+    fn sortindex(&self, d: &[T]) -> Vec<usize>;
+}
+
+/// Closures quantifying items as f64 are used by hashsort.
+/// Beware that integers above 2^53 may be mapped to equal f64 and then mis-ordered.
+impl<T,F> Quantify<T> for F where T: PartialOrd+Clone, F: Copy + Fn(&T) -> f64 {
+    fn sortindex(&self, d: &[T]) -> Vec<usize> { d.hashsort_indexed(*self) }
+}
+
+/// Exact integer (or any `Ord`) keys of the items, sorted stably by comparison, without any loss.
+/// The keys should be ordered consistently with the `PartialOrd` of T, which is used by `search`.
+#[derive(Clone,Copy)]
+pub struct Exact<F>(pub F);
+
+impl<T,K,F> Quantify<T> for Exact<F> where K: Ord, F: Fn(&T) -> K {
+    fn sortindex(&self, d: &[T]) -> Vec<usize> {
+        let mut idx = trivindex(true, d.len());
+        idx.sort_by_key(|&i| (self.0)(&d[i]));
+        idx
+    }
+}
+
 /// Mutable methods for &mut Set<T>
 pub trait MutSetOps<T> {
     /// Makes a Set unordered
     fn munordered(&mut self);
     /// Makes a Set ordered
     fn mordered(&mut self, quantify: impl Copy + Fn(&T) -> f64, asc:bool);
+    /// Makes a Set ordered, sorting unordered data by any `Quantify`, e.g. `Exact` keys
+    fn mordered_by(&mut self, quantify: impl Quantify<T>, asc:bool);
     /// Makes any Set indexed
     fn mindexed(&mut self, quantify: impl Copy + Fn(&T) -> f64, asc:bool);
+    /// Makes any Set indexed, sorting unordered data by any `Quantify`, e.g. `Exact` keys
+    fn mindexed_by(&mut self, quantify: impl Quantify<T>, asc:bool);
     /// Converts any Set type to ranked
     fn mranked(&mut self,asc:bool);
     /// General converter: s -> Set of the same type and order as self
     fn msame(&mut self, s:&mut Self, quantify: impl Copy + Fn(&T) -> f64);
+    /// General converter, sorting unordered data by any `Quantify`, e.g. `Exact` keys
+    fn msame_by(&mut self, s:&mut Self, quantify: impl Quantify<T>);
     /// Deletes the first item from self
     fn mdelete(&mut self, item:T) -> bool;
     /// Deletes all occurrences of a matching item from self, returns their count
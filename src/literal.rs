@@ -0,0 +1,23 @@
+//! Set literals. `set![...]` builds a Set of any SType from a list of items:
+//!
+//! - `set![]` is the `EMPTYSET`, `set![a, b, c]` and `set![unordered; a, b, c]` are Unordered.
+//! - `set![ordered asc; a, b, c]`, likewise `indexed` and `ranked`, sort in `asc` or `desc` order (asc when omitted).
+//! - `set![view asc; a, b, c]` is a const `SetView` over items already sorted in that order, see `SetView::new_sorted`.
+
+/// Set literal of any SType, see the module `literal`
+#[macro_export]
+macro_rules! set {
+    (@asc) => { true };
+    (@asc asc) => { true };
+    (@asc desc) => { false };
+    () => { $crate::Set::EMPTYSET };
+    (unordered; $($x:expr),* $(,)?) => { $crate::Set::new($crate::SType::Unordered, &[$($x),*], true) };
+    (ordered $($o:ident)?; $($x:expr),* $(,)?) => {
+        $crate::Set::new($crate::SType::Ordered, &[$($x),*], $crate::set!(@asc $($o)?)) };
+    (indexed $($o:ident)?; $($x:expr),* $(,)?) => {
+        $crate::Set::new($crate::SType::Indexed, &[$($x),*], $crate::set!(@asc $($o)?)) };
+    (ranked $($o:ident)?; $($x:expr),* $(,)?) => {
+        $crate::Set::new($crate::SType::Ranked, &[$($x),*], $crate::set!(@asc $($o)?)) };
+    (view $($o:ident)?; $($x:expr),* $(,)?) => { $crate::SetView::new_sorted(&[$($x),*], $crate::set!(@asc $($o)?)) };
+    ($($x:expr),+ $(,)?) => { $crate::Set::new($crate::SType::Unordered, &[$($x),*], true) };
+}
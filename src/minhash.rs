@@ -0,0 +1,185 @@
+use crate::prelude::*;
+use crate::{SType,Set};
+#[cfg(feature = "std")]
+use std::collections::HashMap as Map;
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap as Map; // no hashed maps without std
+use core::hash::{Hash,Hasher};
+
+/// Mersenne prime 2^61-1, the modulus of the universal hash permutations
+const MERSENNE61: u64 = (1_u64 << 61) - 1;
+
+/// Fnv-1a hasher, used instead of `DefaultHasher`,
+/// so that the signatures are stable across Rust releases and platforms
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 { self.0 }
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// Stable 64 bit hash of any hashable item
+fn hash64<T: Hash + ?Sized>(item: &T) -> u64 {
+    let mut h = Fnv(0xcbf29ce484222325);
+    item.hash(&mut h);
+    h.finish()
+}
+
+/// Splitmix64 step, deterministic generator of the permutation coefficients
+fn splitmix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// (a*x+b) mod 2^61-1, the universal hash of x
+fn permute(a: u64, b: u64, x: u64) -> u64 {
+    let r = (a as u128 * (x % MERSENNE61) as u128 + b as u128) % MERSENNE61 as u128;
+    r as u64
+}
+
+/// MinHash sketch (signature) of a set
+#[derive(Default,Clone,PartialEq,Eq,Debug)]
+pub struct MinHash {
+    /// seed that generated the permutations
+    pub seed: u64,
+    /// minimum permuted hash value for each permutation
+    pub signature: Vec<u64>
+}
+
+impl MinHash {
+    /// Sketches Set s, using perms pseudo random permutations generated from seed.
+    /// Repeated items make no difference.
+    /// Empty set has all of its signature values equal to u64::MAX.
+    pub fn new<T: Hash>(s: &Set<T>, perms: usize, seed: u64) -> Self {
+        let mut state = seed;
+        let coefs: Vec<(u64,u64)> = (0..perms)
+            .map(|_| (splitmix(&mut state) % (MERSENNE61-1) + 1, splitmix(&mut state) % MERSENNE61))
+            .collect();
+        let mut signature = vec![u64::MAX; perms];
+        if let SType::Empty = s.stype { return MinHash{ seed, signature } };
+        for item in &s.data {
+            let x = hash64(item);
+            for (sig,&(a,b)) in signature.iter_mut().zip(&coefs) {
+                let h = permute(a,b,x);
+                if h < *sig { *sig = h };
+            }
+        }
+        MinHash{ seed, signature }
+    }
+
+    /// Number of permutations (length of the signature)
+    pub fn len(&self) -> usize { self.signature.len() }
+
+    /// True when the signature has no permutations
+    pub fn is_empty(&self) -> bool { self.signature.is_empty() }
+
+    /// Estimated Jaccard similarity: the fraction of agreeing signature values.
+    /// Panics when the two sketches were not made with the same seed and length.
+    pub fn jaccard(&self, other: &Self) -> f64 {
+        assert!(self.seed == other.seed && self.len() == other.len(),
+            "MinHash::jaccard: sketches of different seeds or lengths");
+        if self.is_empty() { return 0_f64 };
+        let agree = self.signature.iter().zip(&other.signature).filter(|(a,b)| a == b).count();
+        agree as f64 / self.len() as f64
+    }
+}
+
+/// Banded locality sensitive hashing index over the MinHash sketches of many sets.
+/// Sets whose sketches agree on all rows of at least one band become candidates.
+/// The probability of that is 1-(1-j^rows)^bands, for sets of Jaccard similarity j.
+#[derive(Clone)]
+pub struct LshIndex<T> {
+    /// number of bands
+    pub bands: usize,
+    /// rows (signature values) in each band
+    pub rows: usize,
+    /// seed of the MinHash permutations
+    pub seed: u64,
+    /// indexed sets, their ids are their subscripts
+    pub sets: Vec<Set<T>>,
+    /// MinHash sketches of the indexed sets
+    pub sketches: Vec<MinHash>,
+    /// one hash table per band: band hash -> ids of the sets
+    buckets: Vec<Map<u64,Vec<usize>>>
+}
+
+impl<T> LshIndex<T> where T: Copy+PartialOrd+Default+Hash {
+
+    /// New empty index, using sketches of bands*rows permutations
+    pub fn new(bands: usize, rows: usize, seed: u64) -> Self {
+        assert!(bands > 0 && rows > 0, "LshIndex::new: bands and rows must be positive");
+        LshIndex{ bands, rows, seed, sets: Vec::new(), sketches: Vec::new(),
+            buckets: vec![Map::new(); bands] }
+    }
+
+    /// Number of indexed sets
+    pub fn len(&self) -> usize { self.sets.len() }
+
+    /// True when no sets have been indexed
+    pub fn is_empty(&self) -> bool { self.sets.is_empty() }
+
+    /// MinHash sketch of s, compatible with this index
+    pub fn sketch(&self, s: &Set<T>) -> MinHash {
+        MinHash::new(s, self.bands*self.rows, self.seed)
+    }
+
+    /// Hashes of each band of the sketch
+    fn band_hashes<'a>(&'a self, mh: &'a MinHash) -> impl Iterator<Item=u64> + 'a {
+        mh.signature.chunks(self.rows).map(hash64)
+    }
+
+    /// Adds a copy of set s to the index, returns its id
+    pub fn insert(&mut self, s: &Set<T>) -> usize {
+        let id = self.sets.len();
+        let mh = self.sketch(s);
+        let hashes: Vec<u64> = self.band_hashes(&mh).collect();
+        for (bucket,h) in self.buckets.iter_mut().zip(hashes) {
+            bucket.entry(h).or_default().push(id);
+        }
+        self.sets.push(s.clone());
+        self.sketches.push(mh);
+        id
+    }
+
+    /// Ids of the indexed sets sharing at least one band with s, in ascending order
+    pub fn candidates(&self, s: &Set<T>) -> Vec<usize> {
+        let mh = self.sketch(s);
+        let mut ids: Vec<usize> = self.band_hashes(&mh).zip(&self.buckets)
+            .filter_map(|(h,bucket)| bucket.get(&h))
+            .flatten().copied().collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Candidates with their estimated (MinHash) similarity of at least threshold,
+    /// in descending order of similarity
+    pub fn query(&self, s: &Set<T>, threshold: f64) -> Vec<(usize,f64)> {
+        let mh = self.sketch(s);
+        self.ranked(self.candidates(s), |id| mh.jaccard(&self.sketches[id]), threshold)
+    }
+
+    /// Candidates verified by their exact Jaccard similarity of at least threshold,
+    /// in descending order of similarity
+    pub fn query_exact(&self, s: &Set<T>, threshold: f64) -> Vec<(usize,f64)> where T: 'static {
+        self.ranked(self.candidates(s), |id| self.sets[id].jaccard(s), threshold)
+    }
+
+    /// Scores the ids, keeps those at or above threshold, most similar first
+    fn ranked(&self, ids: Vec<usize>, score: impl Fn(usize) -> f64, threshold: f64) -> Vec<(usize,f64)> {
+        let mut res: Vec<(usize,f64)> = ids.into_iter()
+            .map(|id| (id,score(id)))
+            .filter(|&(_,j)| j >= threshold)
+            .collect();
+        res.sort_by(|a,b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        res
+    }
+}
@@ -1,8 +1,25 @@
 #![warn(missing_docs)]
-use crate::{trivindex,SType,Set,MutSetOps};
-use indxvec::{Indices,Vecops,Mutops};
+use crate::prelude::*;
+use crate::{trivindex,SType,Set,MutSetOps,Permutation,Quantify,gallop,simd};
+use crate::gallop::AscView;
+use crate::simd::SetOp;
+use crate::compat::{Indices,Vecops,Mutops};
 
-impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
+/// Sort position before any items equal to the inserted one, in a sequence of n sorted items,
+/// given predicates of the i-th item being before it in ascending (less) or descending (greater) order.
+/// Unlike indxvec `binsearch`, the order is not guessed from the end items,
+/// which fails for a single item, or all equal items.
+pub(crate) fn insertpos(asc: bool, n: usize, less: impl Fn(usize) -> bool, greater: impl Fn(usize) -> bool) -> usize {
+    let before = |i: usize| if asc { less(i) } else { greater(i) };
+    let (mut lo, mut hi) = (0, n);
+    while lo < hi {
+        let mid = lo + (hi-lo)/2;
+        if before(mid) { lo = mid + 1 } else { hi = mid }
+    }
+    lo
+}
+
+impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default+'static {
 
     /// Makes a Set unordered
     /// Caution: this just throws away the valuable index!
@@ -10,41 +27,47 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
         match self.stype {
             SType::Empty | SType::Unordered => return, // no op
             SType::Ordered => (), // leave data as is, just change SType below
-            SType::Indexed | SType::Ranked  => self.index = Vec::new() // remove the index
+            SType::Indexed | SType::Ranked  => self.index = Permutation(Vec::new()) // remove the index
         }
         self.stype = SType::Unordered;
         // ascending field has no meaning for unordered, so leaving it as it is 
     }
 
     /// Makes a Set ordered
-    fn mordered(&mut self, quantify: impl Copy + Fn(&T) -> f64, asc:bool) {
+    fn mordered(&mut self, quantify: impl Copy + Fn(&T) -> f64, asc:bool) { self.mordered_by(quantify, asc) }
+
+    /// Makes a Set ordered, sorting unordered data by any Quantify
+    fn mordered_by(&mut self, quantify: impl Quantify<T>, asc:bool) {
         match self.stype {
             SType::Empty => return, // no op
-            SType::Unordered => { self.data.muthashsort(quantify); if !asc { self.data.mutrevs() } },
+            SType::Unordered => self.data = quantify.sortindex(&self.data).unindex(&self.data, asc),
             SType::Ordered => if self.ascending != asc { self.data.mutrevs() }, 
             SType::Indexed => { 
                 self.data = self.index.unindex(&self.data, self.ascending == asc);
-                self.index = Vec::new(); },
+                self.index = Permutation(Vec::new()); },
             SType::Ranked => {
                 self.data = self.index.invindex().unindex(&self.data, self.ascending == asc);
-                self.index = Vec::new(); }
+                self.index = Permutation(Vec::new()); }
         } 
         self.stype = SType::Ordered; // new SType 
         self.ascending = asc;  // new ordering    
     }
 
     /// Makes any Set indexed
-    fn mindexed(&mut self, quantify: impl Copy + Fn(&T) -> f64, asc:bool) { 
-        match self.stype { 
-            SType::Empty => return, // empty set, no op 
+    fn mindexed(&mut self, quantify: impl Copy + Fn(&T) -> f64, asc:bool) { self.mindexed_by(quantify, asc) }
+
+    /// Makes any Set indexed, sorting unordered data by any Quantify
+    fn mindexed_by(&mut self, quantify: impl Quantify<T>, asc:bool) { 
+        match self.kind() { 
+            SType::Empty => if let SType::Empty = self.stype { return }, // typed sets without data just change type
             SType::Unordered => {                 
-                self.index = self.data.hashsort_indexed(quantify);
-                if !asc { self.index.mutrevs(); }; },
-            SType::Ordered => self.index = trivindex(self.ascending == asc,self.data.len()),
-            SType::Indexed => if self.ascending != asc { self.index.mutrevs() },
+                self.index = Permutation(quantify.sortindex(&self.data));
+                if !asc { self.index.0.mutrevs(); }; },
+            SType::Ordered => self.index = Permutation(trivindex(self.ascending == asc,self.data.len())),
+            SType::Indexed => if self.ascending != asc { self.index.0.mutrevs() },
             SType::Ranked => {
-                if self.ascending != asc { self.index = self.index.complindex() }; 
-                self.index = self.index.invindex(); }, 
+                if self.ascending != asc { self.index = self.index.complement() }; 
+                self.index = self.index.inverse(); }, 
         }
         self.stype = SType::Indexed; // new SType 
         self.ascending = asc;  // new ordering 
@@ -52,16 +75,16 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
 
     /// Converts any Set type to ranked
     fn mranked(&mut self,asc:bool) {
-        match self.stype {
-            SType::Empty => return, // empty set, no op 
+        match self.kind() {
+            SType::Empty => if let SType::Empty = self.stype { return }, // typed sets without data just change type
             SType::Unordered =>  {                 
-                self.index = self.data.mergesort_indexed().invindex();
-                if !asc { self.index.complindex(); }; },
-            SType::Ordered => self.index = trivindex(self.ascending == asc,self.data.len()),
+                self.index = Permutation(self.data.mergesort_indexed().invindex());
+                if !asc { self.index = self.index.complement(); }; },
+            SType::Ordered => self.index = Permutation(trivindex(self.ascending == asc,self.data.len())),
             SType::Indexed => {
-                if self.ascending != asc { self.index.mutrevs() }; 
-                self.index = self.index.invindex(); }, 
-            SType::Ranked => if self.ascending != asc { self.index = self.index.complindex() }
+                if self.ascending != asc { self.index.0.mutrevs() }; 
+                self.index = self.index.inverse(); }, 
+            SType::Ranked => if self.ascending != asc { self.index = self.index.complement() }
         } 
         self.stype = SType::Ranked; // new SType 
         self.ascending = asc;  // new ordering    
@@ -69,12 +92,15 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
 
     /// General converter: s -> Set of the same type and order as self
     /// self only serves as a template for the type and order and is not involved in the conversion
-    fn msame(&mut self, s:&mut Self, quantify: impl Copy + Fn(&T) -> f64) { 
+    fn msame(&mut self, s:&mut Self, quantify: impl Copy + Fn(&T) -> f64) { self.msame_by(s, quantify) }
+
+    /// General converter, sorting unordered data by any Quantify
+    fn msame_by(&mut self, s:&mut Self, quantify: impl Quantify<T>) { 
         match self.stype { 
             SType::Empty => *s = Set::EMPTYSET, //  was Default::default()
             SType::Unordered => s.munordered(), 
-            SType::Ordered => s.mordered(quantify, self.ascending),
-            SType::Indexed => s.mindexed(quantify,self.ascending),
+            SType::Ordered => s.mordered_by(quantify, self.ascending),
+            SType::Indexed => s.mindexed_by(quantify,self.ascending),
             SType::Ranked => s.mranked(self.ascending)
         }
     }  
@@ -82,7 +108,7 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
     /// Deletes an item from self
     /// Returns false if item not found 
     fn mdelete(&mut self, item:T) -> bool where Self:Sized {
-        match self.stype {
+        match self.kind() {
             SType::Empty => false, // empty set
             SType::Unordered => {
                 if let Some(i) = self.data.member(item,true) {
@@ -102,8 +128,8 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
                 if r.is_empty() { return false; };
                 let datasub = self.index[r.start];
                 self.data.remove(datasub); // remove + shift data , preserves ordering
-                self.index.remove(r.start); // remove + shift data , preserves ordering               
-                for idxitem in  &mut self.index { // repair the whole sort index
+                self.index.0.remove(r.start); // remove + shift data , preserves ordering               
+                for idxitem in  &mut self.index.0 { // repair the whole sort index
                     if *idxitem > datasub { *idxitem -= 1 };
                 }
                 true },
@@ -118,7 +144,7 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
                 for idxitem in &mut sortindex { // repair the whole sort index
                     if *idxitem > datasub { *idxitem -= 1 };
                 }
-                self.index = sortindex.invindex(); // reconstruct rank index
+                self.index = Permutation(sortindex.invindex()); // reconstruct rank index
                 true },
         }
     }  
@@ -127,7 +153,7 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
     /// Returns number found and deleted 
     fn mdeleteall(&mut self, item:T) -> usize where Self:Sized {
         let mut count = 0_usize;
-        match self.stype {
+        match self.kind() {
             SType::Empty => 0, // empty set
             SType::Unordered => {
                 while let Some(i) = self.data.member(item,true) {
@@ -152,7 +178,7 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
                 let count = r.len();
                 ord_data.drain(r);
                 self.data = ord_data;
-                self.index = trivindex(self.ascending,self.data.len());
+                self.index = Permutation(trivindex(self.ascending,self.data.len()));
                 count },
 
             SType::Ranked => {
@@ -162,7 +188,7 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
                 let count = r.len();
                 ord_data.drain(r);
                 self.data = ord_data;
-                self.index = trivindex(self.ascending,self.data.len());
+                self.index = Permutation(trivindex(self.ascending,self.data.len()));
                 count } 
         }
     }  
@@ -176,25 +202,30 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
             },
             SType::Unordered => self.data.push(item), 
             SType::Ordered => {
-                // binsearch finds the right sort position
-                let range = self.data.binsearch(&item);
-                self.data.insert(range.start,item); // shifts the rest  
+                // binary search finds the right sort position
+                let pos = insertpos(self.ascending, self.data.len(), |i| self.data[i] < item, |i| self.data[i] > item);
+                self.data.insert(pos,item); // shifts the rest  
             },
             SType::Indexed => {
-                let irange = self.data.binsearch_indexed(&self.index,&item); 
+                let pos = insertpos(self.ascending, self.index.len(),
+                    |i| self.data[self.index[i]] < item, |i| self.data[self.index[i]] > item);
                 // simply push the item to the end of unordered data self.data
                 self.data.push(item);
                 // and insert its subscipt into the right place in the sort index    
-                self.index.insert(irange.start,self.data.len()-1);                
+                self.index.0.insert(pos,self.data.len()-1);                
 
             }
             SType::Ranked => {
                // invert the rank index to get the sort index position
-                let irange = self.data.binsearch_indexed(&self.index.invindex(),&item);
+                let sortindex = self.index.invindex();
+                let pos = insertpos(self.ascending, sortindex.len(),
+                    |i| self.data[sortindex[i]] < item, |i| self.data[sortindex[i]] > item);
                 // simply push the new item to the end of unordered data self.data
                 self.data.push(item);
-                // and insert its index position (rank) into the same place in the rank index    
-                self.index.push(irange.start);
+                // shift up the ranks of the items that now follow it
+                for rank in &mut self.index.0 { if *rank >= pos { *rank += 1 } };
+                // and append its rank, the insertion position in the sort index
+                self.index.0.push(pos);
             }
         };
     }
@@ -211,11 +242,11 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
             },
             SType::Indexed => {
                 self.ascending = !self.ascending;
-                self.index.mutrevs(); 
+                self.index.0.mutrevs(); 
             },
             SType::Ranked => {
                 self.ascending = !self.ascending;
-                self.index = self.index.complindex();                
+                self.index = self.index.complement();                
             }
         }
     }
@@ -234,38 +265,45 @@ impl<T> MutSetOps<T> for Set<T> where T:Copy+PartialOrd+Default {
                 let mut orddata = self.index.unindex(&self.data,self.ascending);
                 orddata.dedup();
                 self.data = orddata; // resets data to ordered
-                self.index = trivindex(self.ascending, self.data.len());
+                self.index = Permutation(trivindex(self.ascending, self.data.len()));
             },
             SType::Ranked => { // spoofed by sorted data and trivial index
                 let mut orddata = self.index.invindex().unindex(&self.data,self.ascending);
                 orddata.dedup();
                 self.data = orddata; // resets data to ordered
-                self.index = trivindex(self.ascending, self.data.len());       
+                self.index = Permutation(trivindex(self.ascending, self.data.len()));       
             }
         }
     }
 
-    /// sets union
+    /// sets union, by the simd kernels for u32 and u64
     fn munion(&mut self, s: &Self) {
         let mut selford = self.to_ordered(true);
         let sord = s.to_ordered(true);
-        selford.data = selford.data.merge(&sord.data);
+        selford.data = simd::sorted_op(SetOp::Union, &selford.data, &sord.data)
+            .unwrap_or_else(|| selford.data.merge(&sord.data));
         *self = self.to_same(&selford); // back to original type and order 
     }
 
-    /// Intersection of two unordered sets, assigned to self
+    /// Intersection of two sets, assigned to self.
+    /// Neither operand is converted, unless Unordered (see `gallop::intersect`).
+    /// Ascending u32 and u64 data are intersected by the simd kernels
     fn mintersection(&mut self, s: &Self) {
-        let mut selford = self.to_ordered(true);
-        let sord = s.to_ordered(true);
-        selford.data = selford.data.intersect(&sord.data);
+        let (v1, v2) = (AscView::new(self), AscView::new(s));
+        let data = match (v1.as_slice(), v2.as_slice()) {
+            (Some(a), Some(b)) => simd::sorted_op(SetOp::Intersect, a, b),
+            _ => None
+        }.unwrap_or_else(|| gallop::intersect_views(&v1, &v2));
+        let selford = Set{ stype:SType::Ordered, ascending:true, data, index:Permutation(Vec::new()) };
         *self = self.to_same(&selford); // back to original type and order 
     }
 
-    /// Complement of s in self (i.e. self -= s)
+    /// Complement of s in self (i.e. self -= s), by the simd kernels for u32 and u64
     fn mdifference(&mut self, s: &Self) {
         let mut selford = self.to_ordered(true);
         let sord = s.to_ordered(true);
-        selford.data = selford.data.diff(&sord.data);
+        selford.data = simd::sorted_op(SetOp::Difference, &selford.data, &sord.data)
+            .unwrap_or_else(|| selford.data.diff(&sord.data));
         *self = self.to_same(&selford); // back to original type and order
     }    
 }
@@ -0,0 +1,106 @@
+use crate::prelude::*;
+use crate::{SType,Set};
+use alloc::string::{String,ToString};
+use core::{fmt,str::FromStr};
+
+/// Error of parsing a Set from text, locating the offending token
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct ParseSetError {
+    /// byte position of the token in the parsed text
+    pub position: usize,
+    /// the offending token
+    pub token: String,
+    /// what is wrong with it
+    pub reason: &'static str
+}
+
+impl fmt::Display for ParseSetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "set parse error: {} `{}` at position {}", self.reason, self.token, self.position)
+    }
+}
+
+impl core::error::Error for ParseSetError {}
+
+fn error(position: usize, token: &str, reason: &'static str) -> ParseSetError {
+    ParseSetError{ position, token: token.to_string(), reason }
+}
+
+/// Splits s into its item tokens with their positions, removing any enclosing `{}` or `[]`.
+/// Items are separated by commas and/or whitespace, one trailing comma is allowed.
+fn tokens(s: &str) -> Result<Vec<(usize,&str)>,ParseSetError> {
+    let start = s.len() - s.trim_start().len();
+    let t = s.trim();
+    let (mut offset, mut body) = (start, t);
+    match t.chars().next() {
+        Some(open @ ('{' | '[')) => {
+            let close = if open == '{' { '}' } else { ']' };
+            if t.len() < 2 || !t.ends_with(close) { return Err(error(start, &t[..1], "unclosed bracket")) };
+            offset += 1;
+            body = &t[1..t.len()-1];
+        },
+        _ => if let Some(last @ ('}' | ']')) = t.chars().last() {
+            return Err(error(start + t.len() - 1, &t[t.len()-1..], if last == '}' { "unmatched `}`" } else { "unmatched `]`" }))
+        }
+    };
+    let mut toks = Vec::new();
+    let (mut tokstart, mut itemsince) = (None, false);
+    for (i,c) in body.char_indices() {
+        if c == ',' || c.is_whitespace() {
+            if let Some(b) = tokstart.take() { toks.push((offset+b, &body[b..i])); itemsince = true };
+            if c == ',' {
+                if !itemsince { return Err(error(offset+i, ",", "missing item before")) };
+                itemsince = false;
+            }
+        } else if tokstart.is_none() { tokstart = Some(i) }
+    }
+    if let Some(b) = tokstart { toks.push((offset+b, &body[b..])) };
+    Ok(toks)
+}
+
+/// Largest number of items a range may stand for, so that text can not demand huge allocations
+pub const MAX_RANGE_LEN: i128 = 1 << 20;
+
+/// Largest number of items of all the ranges and items of one text together
+pub const MAX_ITEMS: usize = 1 << 22;
+
+/// Parses one token: an item, or an integer range `a..b` (b exclusive) or `a..=b`
+fn items<T: FromStr>(position: usize, tok: &str, res: &mut Vec<T>) -> Result<(),ParseSetError> {
+    let toomany = || error(position, tok, "more than MAX_ITEMS items");
+    let Some((lo, hi)) = tok.split_once("..") else {
+        if res.len() >= MAX_ITEMS { return Err(toomany()) };
+        res.push(tok.parse().map_err(|_| error(position, tok, "invalid item"))?);
+        return Ok(());
+    };
+    let (hi, inclusive) = match hi.strip_prefix('=') { Some(h) => (h, true), None => (hi, false) };
+    let bound = |b: &str| b.parse::<i128>().map_err(|_| error(position, tok, "range bound not an integer"));
+    let (lo, hi) = (bound(lo)?, bound(hi)?);
+    let hi = if inclusive { Some(hi) } else { hi.checked_sub(1) };
+    let Some(hi) = hi.filter(|&hi| hi >= lo) else { return Err(error(position, tok, "empty range")) };
+    if hi.checked_sub(lo).is_none_or(|span| span >= MAX_RANGE_LEN) {
+        return Err(error(position, tok, "range longer than MAX_RANGE_LEN")) };
+    if res.len() + (hi - lo) as usize >= MAX_ITEMS { return Err(toomany()) };
+    for i in lo..=hi {
+        res.push(i.to_string().parse().map_err(|_| error(position, tok, "range item out of type"))?);
+    }
+    Ok(())
+}
+
+impl<T> Set<T> where T: Copy+PartialOrd+Default+FromStr {
+
+    /// Parses text into a Set of set_type, in asc order.
+    /// Accepts `{3, 1, 2}`, `[3, 1, 2]` or a plain list, separated by commas and/or whitespace,
+    /// with integer ranges `a..b` (b exclusive) and `a..=b` standing for their items,
+    /// at most `MAX_RANGE_LEN` of them per range and `MAX_ITEMS` in all.
+    pub fn parse_as(set_type: SType, s: &str, asc: bool) -> Result<Self,ParseSetError> {
+        let mut data = Vec::new();
+        for (position, tok) in tokens(s)? { items(position, tok, &mut data)? };
+        Ok(Set::new(set_type, &data, asc))
+    }
+}
+
+/// Parses an Unordered Set, keeping the items in the order of the text, see `Set::parse_as`
+impl<T> FromStr for Set<T> where T: Copy+PartialOrd+Default+FromStr {
+    type Err = ParseSetError;
+    fn from_str(s: &str) -> Result<Self,ParseSetError> { Set::parse_as(SType::Unordered, s, true) }
+}
@@ -0,0 +1,134 @@
+use crate::prelude::*;
+use crate::{trivindex,ranking::kendall_distance};
+use crate::compat::{Indices,Printing,Vecops};
+use core::ops::Deref;
+
+/// Validated permutation of 0..n, e.g. the sort index or the ranks of a Set.
+/// Dereferences to `&[usize]`, so all the read only slice methods apply.
+#[derive(Default,Clone,PartialEq,Eq,Hash,Debug)]
+pub struct Permutation(pub(crate) Vec<usize>);
+
+impl Deref for Permutation {
+    type Target = [usize];
+    fn deref(&self) -> &[usize] { &self.0 }
+}
+
+impl AsRef<[usize]> for Permutation {
+    fn as_ref(&self) -> &[usize] { &self.0 }
+}
+
+impl PartialEq<Vec<usize>> for Permutation {
+    fn eq(&self, other: &Vec<usize>) -> bool { self.0 == *other }
+}
+
+impl PartialEq<[usize]> for Permutation {
+    fn eq(&self, other: &[usize]) -> bool { self.0 == other }
+}
+
+/// Implementation of Display trait for struct Permutation.
+impl core::fmt::Display for Permutation {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f,"{}",self.0.to_str())
+    }
+}
+
+impl From<Permutation> for Vec<usize> {
+    fn from(p: Permutation) -> Self { p.0 }
+}
+
+impl Permutation {
+
+    /// Validates v as a permutation of 0..v.len(), None when it is not one
+    pub fn new(v: Vec<usize>) -> Option<Self> {
+        let mut seen = vec![false; v.len()];
+        for &i in &v {
+            if i >= v.len() || seen[i] { return None };
+            seen[i] = true;
+        }
+        Some(Permutation(v))
+    }
+
+    /// Identity permutation of size n
+    pub fn identity(n: usize) -> Self { Permutation(trivindex(true,n)) }
+
+    /// Reversed identity permutation of size n
+    pub fn reversed(n: usize) -> Self { Permutation(trivindex(false,n)) }
+
+    /// Ascending sort index of data d, the permutation that sorts it
+    pub fn sortindex<T: PartialOrd+Clone>(d: &[T]) -> Self { Permutation(d.mergesort_indexed()) }
+
+    /// The underlying subscripts
+    pub fn as_slice(&self) -> &[usize] { &self.0 }
+
+    /// Consumes self, returning the underlying Vec
+    pub fn into_vec(self) -> Vec<usize> { self.0 }
+
+    /// Inverse permutation, e.g. sort index <-> ranks
+    pub fn inverse(&self) -> Self { Permutation(self.0.invindex()) }
+
+    /// Reversed order of the subscripts (toggles a sort index between ascending/descending)
+    pub fn reverse(&self) -> Self { Permutation(self.0.revs()) }
+
+    /// Complement n-1-i of each subscript (toggles ranks between ascending/descending)
+    pub fn complement(&self) -> Self { Permutation(self.0.complindex()) }
+
+    /// Composition: the permutation applying first other then self, i.e. result\[i\] = self\[other\[i\]\]
+    pub fn compose(&self, other: &Self) -> Self {
+        assert_eq!(self.len(), other.len(), "Permutation::compose: different lengths");
+        Permutation(other.0.iter().map(|&i| self.0[i]).collect())
+    }
+
+    /// Out of place application to v: result\[i\] = v\[self\[i\]\].
+    /// For a sort index, this gives the sorted v.
+    pub fn apply<T: Clone>(&self, v: &[T]) -> Vec<T> {
+        assert_eq!(self.len(), v.len(), "Permutation::apply: different lengths");
+        self.0.unindex(v, true)
+    }
+
+    /// In place application to v, with the same result as `apply`,
+    /// by following the cycles of the permutation
+    pub fn apply_mut<T>(&self, v: &mut [T]) {
+        assert_eq!(self.len(), v.len(), "Permutation::apply_mut: different lengths");
+        let mut done = vec![false; v.len()];
+        for start in 0..v.len() {
+            if done[start] { continue };
+            done[start] = true;
+            let mut i = start;
+            loop {
+                let next = self.0[i];
+                if next == start { break };
+                v.swap(i, next);
+                done[next] = true;
+                i = next;
+            }
+        }
+    }
+
+    /// Disjoint cycles of the permutation, including the fixed points as cycles of length one.
+    /// Each cycle starts with its smallest element, i -> self\[i\] -> ...
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut done = vec![false; self.len()];
+        let mut res = Vec::new();
+        for start in 0..self.len() {
+            if done[start] { continue };
+            let mut cycle = Vec::new();
+            let mut i = start;
+            while !done[i] { done[i] = true; cycle.push(i); i = self.0[i]; }
+            res.push(cycle);
+        }
+        res
+    }
+
+    /// Number of inversions: the pairs i<j with self\[i\] > self\[j\]
+    pub fn inversions(&self) -> u64 {
+        kendall_distance(&trivindex(true,self.len()), &self.0)
+    }
+
+    /// True for an even permutation
+    pub fn is_even(&self) -> bool {
+        (self.len() - self.cycles().len()) & 1 == 0
+    }
+
+    /// Sign of the permutation: 1 when even, -1 when odd
+    pub fn sign(&self) -> i8 { if self.is_even() { 1 } else { -1 } }
+}
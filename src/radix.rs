@@ -0,0 +1,103 @@
+use crate::prelude::*;
+use crate::{SType,Set,Permutation};
+use crate::compat::{Indices,Vecops};
+
+/// Inputs shorter than this are sorted by merge sort in `Set::new_auto`
+pub const RADIX_THRESHOLD: usize = 256;
+
+/// Primitive types with an order preserving mapping to u64, for radix sorting
+pub trait RadixKey {
+    /// u64 key, ascending in the same order as self
+    fn radix(&self) -> u64;
+}
+
+macro_rules! unsigned_radix {
+    ($($t:ty),*) => { $( impl RadixKey for $t { fn radix(&self) -> u64 { *self as u64 } } )* }
+}
+unsigned_radix!(u8,u16,u32,u64,usize);
+
+macro_rules! signed_radix {
+    ($($t:ty),*) => { $( impl RadixKey for $t {
+        fn radix(&self) -> u64 { (*self as i64 as u64) ^ (1 << 63) } } )* }
+}
+signed_radix!(i8,i16,i32,i64,isize);
+
+/// -0.0 gets the key of 0.0, as they compare equal.
+/// NaNs get keys beyond the infinities of their sign, whereas merge sort panics on them.
+impl RadixKey for f64 {
+    fn radix(&self) -> u64 {
+        let bits = if *self == 0.0 { 0 } else { self.to_bits() };
+        if bits >> 63 == 1 { !bits } else { bits ^ (1 << 63) }
+    }
+}
+
+impl RadixKey for f32 {
+    fn radix(&self) -> u64 { (*self as f64).radix() }
+}
+
+/// Stable ascending sort index of keys, by LSD radix sort, one byte per pass.
+/// Passes over bytes that are the same in all keys are skipped.
+pub fn radix_sortindex_keys(keys: &[u64]) -> Vec<usize> {
+    let n = keys.len();
+    let mut idx: Vec<usize> = (0..n).collect();
+    let mut buf = vec![0_usize; n];
+    for shift in (0..64).step_by(8) {
+        let mut counts = [0_usize; 256];
+        for &k in keys { counts[((k >> shift) & 0xff) as usize] += 1 };
+        if counts.contains(&n) { continue }; // all the same byte
+        let mut pos = [0_usize; 256];
+        for b in 1..256 { pos[b] = pos[b-1] + counts[b-1] };
+        for &i in &idx {
+            let b = ((keys[i] >> shift) & 0xff) as usize;
+            buf[pos[b]] = i;
+            pos[b] += 1;
+        }
+        core::mem::swap(&mut idx, &mut buf);
+    }
+    idx
+}
+
+/// Stable ascending sort index of d, by radix sort
+pub fn radix_sortindex<T: RadixKey>(d: &[T]) -> Vec<usize> {
+    radix_sortindex_keys(&d.iter().map(|x| x.radix()).collect::<Vec<u64>>())
+}
+
+/// Radix sort constructors, for sets of primitive numeric end types
+impl<T> Set<T> where T: Copy+PartialOrd+Default+RadixKey {
+
+    /// Ascending or descending sort index of d, by radix sort
+    fn radix_index(d: &[T], asc: bool) -> Vec<usize> {
+        if asc { radix_sortindex(d) } else { radix_sortindex(d).revs() }
+    }
+
+    /// All in one Initialiser, as `new`, but sorting by radix sort
+    pub fn new_radix(set_type: SType, d: &[T], asc: bool) -> Self {
+        if d.is_empty() { return Set::EMPTYSET };
+        match set_type {
+            SType::Empty => Set::EMPTYSET,
+            SType::Unordered => Set::new_unordered(d),
+            SType::Ordered => Set{ stype:SType::Ordered, ascending:asc,
+                data:Self::radix_index(d,asc).unindex(d,true), index:Permutation(Vec::new()) },
+            SType::Indexed => Set{ stype:SType::Indexed, ascending:asc, data:d.to_vec(),
+                index:Permutation(Self::radix_index(d,asc)) },
+            SType::Ranked => Set{ stype:SType::Ranked, ascending:asc, data:d.to_vec(),
+                index:Permutation(Self::radix_index(d,asc).invindex()) }
+        }
+    }
+
+    /// Initialiser - creates a new SType::Ordered Set by radix sort
+    pub fn new_ordered_radix(d: &[T], asc: bool) -> Self { Self::new_radix(SType::Ordered, d, asc) }
+
+    /// Initialiser - creates a new SType::Indexed Set by radix sort
+    pub fn new_indexed_radix(d: &[T], asc: bool) -> Self { Self::new_radix(SType::Indexed, d, asc) }
+
+    /// Initialiser - creates a new SType::Ranked Set by radix sort
+    pub fn new_ranked_radix(d: &[T], asc: bool) -> Self { Self::new_radix(SType::Ranked, d, asc) }
+
+    /// All in one Initialiser, choosing radix sort for inputs of at least
+    /// `RADIX_THRESHOLD` items and merge sort (`new`) for the shorter ones
+    pub fn new_auto(set_type: SType, d: &[T], asc: bool) -> Self {
+        if d.len() < RADIX_THRESHOLD { Self::new(set_type, d, asc) }
+        else { Self::new_radix(set_type, d, asc) }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::prelude::*;
+use crate::compat::{Vecops,sqrt};
+use crate::Permutation;
+
+/// Methods of ranking the tied (equal) items
+#[derive(Default,Clone,Copy,PartialEq,Eq,Debug)]
+pub enum RankMethod {
+    /// average of the ordinal ranks of the tied items (fractional)
+    #[default]
+    Average,
+    /// the lowest ordinal rank of the tied items ("competition" ranking)
+    Min,
+    /// the highest ordinal rank of the tied items
+    Max,
+    /// consecutive ranks of the distinct values, without gaps
+    Dense,
+    /// distinct ordinal ranks, tied items ranked in the order of their appearance
+    Ordinal
+}
+
+/// Ranks of data d, counted from 0, in ascending or descending order,
+/// with tied items ranked by the given method
+pub fn tiedranks<T>(d: &[T], asc: bool, method: RankMethod) -> Vec<f64> where T: PartialOrd+Copy {
+    let n = d.len();
+    let mut ranks = vec![0_f64; n];
+    if n == 0 { return ranks };
+    let mut sortindex = d.mergesort_indexed();
+    // runs of tied items within the (ascending) sort index
+    let mut runs = Vec::new();
+    let mut start = 0;
+    while start < n {
+        let mut end = start + 1;
+        while end < n && d[sortindex[end]] == d[sortindex[start]] { end += 1 };
+        sortindex[start..end].sort_unstable(); // ties in the order of appearance
+        runs.push(start..end);
+        start = end;
+    }
+    if !asc { runs.reverse() };
+    let mut first = 0_usize; // ordinal rank of the first item in the current run
+    for (dense,run) in runs.into_iter().enumerate() {
+        let last = first + run.len() - 1;
+        for (i,&sub) in sortindex[run].iter().enumerate() {
+            ranks[sub] = match method {
+                RankMethod::Average => (first + last) as f64 / 2.0,
+                RankMethod::Min => first as f64,
+                RankMethod::Max => last as f64,
+                RankMethod::Dense => dense as f64,
+                RankMethod::Ordinal => (first + i) as f64
+            }
+        }
+        first = last + 1;
+    }
+    ranks
+}
+
+/// Ranks of data d in ascending order, counted from 0,
+/// with tied items all given the average of their ordinal ranks
+pub fn avranks<T>(d: &[T]) -> Vec<f64> where T: PartialOrd+Copy {
+    tiedranks(d, true, RankMethod::Average)
+}
+
+/// Sum of t(t-1)/2 over the lengths t of the runs of equal items in sorted v
+fn tiedpairs<T: PartialEq>(v: &[T]) -> u64 {
+    v.chunk_by(|a,b| a == b).map(|run| { let t = run.len() as u64; t*(t-1)/2 }).sum()
+}
+
+/// Sorts v ascending by merge sort, returning the number of inversions (swaps) it took.
+/// Equal items are not counted as inversions.
+fn mergecount<T: PartialOrd+Copy>(v: &mut [T]) -> u64 {
+    let n = v.len();
+    if n < 2 { return 0 };
+    let mid = n/2;
+    let mut swaps = mergecount(&mut v[..mid]) + mergecount(&mut v[mid..]);
+    let mut merged = Vec::with_capacity(n);
+    let (mut i, mut j) = (0, mid);
+    while i < mid && j < n {
+        if v[j] < v[i] { merged.push(v[j]); j += 1; swaps += (mid - i) as u64; }
+        else { merged.push(v[i]); i += 1; }
+    }
+    merged.extend_from_slice(&v[i..mid]);
+    merged.extend_from_slice(&v[j..n]);
+    v.copy_from_slice(&merged);
+    swaps
+}
+
+/// Pearson's correlation of two f64 slices
+fn pearson(x: &[f64], y: &[f64]) -> f64 {
+    let nf = x.len() as f64;
+    let (mx, my) = (x.iter().sum::<f64>()/nf, y.iter().sum::<f64>()/nf);
+    let (mut sxy, mut sx2, mut sy2) = (0_f64, 0_f64, 0_f64);
+    for (&a,&b) in x.iter().zip(y) {
+        let (dx, dy) = (a-mx, b-my);
+        sxy += dx*dy; sx2 += dx*dx; sy2 += dy*dy;
+    }
+    sxy / sqrt(sx2*sy2)
+}
+
+/// Spearman's rho of two equal length data slices,
+/// i.e. Pearson's correlation of their (tie averaged) ranks.
+/// NaN when either slice is constant.
+pub fn spearman<T>(x: &[T], y: &[T]) -> f64 where T: PartialOrd+Copy {
+    assert_eq!(x.len(), y.len(), "spearman: slices of different lengths");
+    pearson(&avranks(x), &avranks(y))
+}
+
+/// Kendall's tau-b of two equal length data slices, corrected for ties.
+/// Knight's O(n log n) algorithm. NaN when either slice is constant.
+pub fn kendall<T>(x: &[T], y: &[T]) -> f64 where T: PartialOrd+Copy {
+    assert_eq!(x.len(), y.len(), "kendall: slices of different lengths");
+    let n = x.len() as u64;
+    let mut pairs: Vec<(T,T)> = x.iter().copied().zip(y.iter().copied()).collect();
+    pairs.sort_by(|a,b| a.partial_cmp(b).expect("kendall comparison failure"));
+    let n0 = n*n.saturating_sub(1)/2;
+    let xs: Vec<T> = pairs.iter().map(|p| p.0).collect();
+    let n1 = tiedpairs(&xs); // pairs tied in x
+    let n3 = tiedpairs(&pairs); // pairs tied in both x and y
+    let mut ys: Vec<T> = pairs.iter().map(|p| p.1).collect();
+    let swaps = mergecount(&mut ys);
+    let n2 = tiedpairs(&ys); // pairs tied in y
+    let concordant_less_discordant = n0 as f64 - (n1 + n2) as f64 + n3 as f64 - 2.0*swaps as f64;
+    concordant_less_discordant / sqrt(((n0 - n1) as f64)*((n0 - n2) as f64))
+}
+
+/// Kendall tau distance between two rank vectors (permutations) of the same items:
+/// the number of item pairs that they order differently.
+/// Panics when they are not both permutations of 0..n.
+pub fn kendall_distance(r1: &[usize], r2: &[usize]) -> u64 {
+    assert_eq!(r1.len(), r2.len(), "kendall_distance: ranks of different lengths");
+    for (i,r) in [r1,r2].into_iter().enumerate() {
+        assert!(Permutation::new(r.to_vec()).is_some(), "kendall_distance: ranks {} are not a permutation of 0..{}", i+1, r.len());
+    }
+    let mut byr1 = vec![0_usize; r1.len()];
+    for (&rank1,&rank2) in r1.iter().zip(r2) { byr1[rank1] = rank2 };
+    mergecount(&mut byr1)
+}
+
+/// Spearman's footrule distance between two rank vectors (permutations) of the same items:
+/// the sum of the absolute differences of their ranks
+pub fn footrule(r1: &[usize], r2: &[usize]) -> u64 {
+    assert_eq!(r1.len(), r2.len(), "footrule: ranks of different lengths");
+    r1.iter().zip(r2).map(|(&a,&b)| a.abs_diff(b) as u64).sum()
+}
@@ -0,0 +1,101 @@
+use crate::prelude::*;
+use crate::{Set,ranking::avranks};
+use crate::compat::{Vecops,abs,cbrt,exp,sqrt};
+
+/// Result of a rank test
+#[derive(Default,Clone,Copy,Debug)]
+pub struct RankTest {
+    /// the test statistic (U, W or H)
+    pub statistic: f64,
+    /// standard normal deviate of the statistic (normal approximation)
+    pub z: f64,
+    /// p-value of the null hypothesis: two-sided for U and W, upper tail for H
+    pub p: f64
+}
+
+impl core::fmt::Display for RankTest {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f,"statistic: {} z: {} p: {}",self.statistic,self.z,self.p)
+    }
+}
+
+/// Complementary error function, with fractional error below 1.2e-7
+fn erfc(x: f64) -> f64 {
+    let z = abs(x);
+    let t = 1.0/(1.0 + 0.5*z);
+    let r = t*exp(-z*z - 1.26551223 + t*(1.00002368 + t*(0.37409196 + t*(0.09678418
+        + t*(-0.18628806 + t*(0.27886807 + t*(-1.13520398 + t*(1.48851587
+        + t*(-0.82215223 + t*0.17087277)))))))));
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+/// Upper tail probability of the standard normal distribution
+pub fn normal_sf(z: f64) -> f64 {
+    0.5*erfc(z/core::f64::consts::SQRT_2)
+}
+
+/// Sum of t^3-t over the lengths t of the groups of tied items in d
+fn tiesum<T: PartialOrd+Copy>(d: &[T]) -> f64 {
+    d.sortm(true).chunk_by(|a,b| a == b).map(|run| { let t = run.len() as f64; t*t*t - t }).sum()
+}
+
+/// Mann-Whitney U (Wilcoxon rank-sum) test of two independent sets.
+/// The statistic is U of the first set, the p-value is two-sided,
+/// from the tie corrected normal approximation with continuity correction.
+pub fn mann_whitney<T>(s1: &Set<T>, s2: &Set<T>) -> RankTest where T: PartialOrd+Copy {
+    let (n1, n2) = (s1.data.len() as f64, s2.data.len() as f64);
+    assert!(n1 > 0.0 && n2 > 0.0, "mann_whitney: empty set");
+    let mut pooled = s1.data.clone();
+    pooled.extend_from_slice(&s2.data);
+    let n = n1 + n2;
+    let r1: f64 = avranks(&pooled).iter().take(s1.data.len()).map(|r| r+1.0).sum();
+    let u = r1 - n1*(n1+1.0)/2.0;
+    let mean = n1*n2/2.0;
+    let sd = sqrt(n1*n2/12.0*((n+1.0) - tiesum(&pooled)/(n*(n-1.0))));
+    let z = (abs(u-mean) - 0.5).max(0.0)/sd;
+    RankTest{ statistic:u, z: if u < mean { -z } else { z }, p:(2.0*normal_sf(z)).min(1.0) }
+}
+
+/// Wilcoxon signed-rank test of paired samples: the equally long data of s1 and s2,
+/// kept in their original order (i.e. in Unordered, Indexed or Ranked sets).
+/// Quantify converts the items to f64 for their differences; zero differences are dropped.
+/// The statistic is min(W+,W-), the p-value is two-sided, from the tie corrected normal approximation.
+pub fn wilcoxon_signed<T>(s1: &Set<T>, s2: &Set<T>, quantify: impl Fn(&T) -> f64) -> RankTest {
+    assert_eq!(s1.data.len(), s2.data.len(), "wilcoxon_signed: sets of different lengths");
+    let diffs: Vec<f64> = s1.data.iter().zip(&s2.data)
+        .map(|(a,b)| quantify(a) - quantify(b))
+        .filter(|&d| d != 0.0).collect();
+    let n = diffs.len() as f64;
+    assert!(n > 0.0, "wilcoxon_signed: all differences are zero");
+    let absdiffs: Vec<f64> = diffs.iter().map(|&d| abs(d)).collect();
+    let wplus: f64 = avranks(&absdiffs).iter().zip(&diffs)
+        .filter(|(_,&d)| d > 0.0).map(|(r,_)| r+1.0).sum();
+    let mean = n*(n+1.0)/4.0;
+    let sd = sqrt(n*(n+1.0)*(2.0*n+1.0)/24.0 - tiesum(&absdiffs)/48.0);
+    let z = (wplus-mean)/sd;
+    RankTest{ statistic:wplus.min(n*(n+1.0)/2.0 - wplus), z, p:(2.0*normal_sf(abs(z))).min(1.0) }
+}
+
+/// Kruskal-Wallis H test of k independent sets, corrected for ties.
+/// The p-value is the upper tail of the chi-squared distribution with k-1 degrees of freedom,
+/// via the Wilson-Hilferty normal approximation.
+pub fn kruskal_wallis<T>(sets: &[&Set<T>]) -> RankTest where T: PartialOrd+Copy {
+    assert!(sets.len() > 1, "kruskal_wallis: needs at least two sets");
+    assert!(sets.iter().all(|s| !s.data.is_empty()), "kruskal_wallis: empty set");
+    let pooled: Vec<T> = sets.iter().flat_map(|s| s.data.iter().copied()).collect();
+    let ranks = avranks(&pooled);
+    let n = pooled.len() as f64;
+    let mut start = 0;
+    let mut sumsq = 0_f64;
+    for s in sets {
+        let ni = s.data.len();
+        let ri: f64 = ranks[start..start+ni].iter().map(|r| r+1.0).sum();
+        sumsq += ri*ri/ni as f64;
+        start += ni;
+    }
+    let h = (12.0/(n*(n+1.0))*sumsq - 3.0*(n+1.0)) / (1.0 - tiesum(&pooled)/(n*n*n - n));
+    let df = (sets.len() - 1) as f64;
+    let v = 2.0/(9.0*df);
+    let z = (cbrt(h/df) - (1.0 - v))/sqrt(v);
+    RankTest{ statistic:h, z, p:normal_sf(z) }
+}
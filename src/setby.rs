@@ -0,0 +1,573 @@
+use crate::prelude::*;
+use crate::{SType,Set,Permutation,trivindex};
+use core::cmp::Ordering;
+use core::ops::Range;
+use core::marker::PhantomData;
+
+/// Ordering of the items of a `SetBy`, used instead of their `PartialOrd`
+pub trait Compare<T> {
+    /// Ascending comparison of a and b
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// Orders the items by their keys, extracted by the closure
+#[derive(Clone,Copy)]
+pub struct ByKey<F>(pub F);
+
+impl<T,K,F> Compare<T> for ByKey<F> where K: PartialOrd, F: Fn(&T) -> K {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a).partial_cmp(&(self.0)(b)).expect("ByKey comparison failure")
+    }
+}
+
+/// Orders the items by the comparator closure
+#[derive(Clone,Copy)]
+pub struct ByCmp<F>(pub F);
+
+impl<T,F> Compare<T> for ByCmp<F> where F: Fn(&T,&T) -> Ordering {
+    fn compare(&self, a: &T, b: &T) -> Ordering { (self.0)(a,b) }
+}
+
+/// One key of a SortSpec: the key extracted by the closure, in asc or desc order
+#[derive(Clone,Copy)]
+pub struct Key<F> { key_fn: F, asc: bool }
+
+/// One key of a SortSpec, borrowed from the item by the closure, in asc or desc order
+#[derive(Clone,Copy)]
+pub struct KeyRef<F> { key_fn: F, asc: bool }
+
+impl<T,K,F> Compare<T> for Key<F> where K: PartialOrd, F: Fn(&T) -> K {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        let o = (self.key_fn)(a).partial_cmp(&(self.key_fn)(b)).expect("SortSpec comparison failure");
+        if self.asc { o } else { o.reverse() }
+    }
+}
+
+impl<T,K,F> Compare<T> for KeyRef<F> where K: PartialOrd+?Sized, F: for<'a> Fn(&'a T) -> &'a K {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        let o = (self.key_fn)(a).partial_cmp((self.key_fn)(b)).expect("SortSpec comparison failure");
+        if self.asc { o } else { o.reverse() }
+    }
+}
+
+/// Comparison of the key of an item with a key value of type V, in the direction of the key
+pub trait KeyValue<T,V> {
+    /// Ordering of the key of a relative to v
+    fn compare_value(&self, a: &T, v: &V) -> Ordering;
+}
+
+impl<T,K,F> KeyValue<T,K> for Key<F> where K: PartialOrd, F: Fn(&T) -> K {
+    fn compare_value(&self, a: &T, v: &K) -> Ordering {
+        let o = (self.key_fn)(a).partial_cmp(v).expect("SortSpec comparison failure");
+        if self.asc { o } else { o.reverse() }
+    }
+}
+
+impl<T,K,F> KeyValue<T,&K> for KeyRef<F> where K: PartialOrd+?Sized, F: for<'a> Fn(&'a T) -> &'a K {
+    fn compare_value(&self, a: &T, v: &&K) -> Ordering {
+        let o = (self.key_fn)(a).partial_cmp(*v).expect("SortSpec comparison failure");
+        if self.asc { o } else { o.reverse() }
+    }
+}
+
+/// The keys of a SortSpec, as a list `(first, (second, ..., ()))`
+pub trait SortKeys<T> {
+    /// Number of keys
+    const LEN: usize;
+    /// Comparison of a and b by the first k keys only
+    fn compare_first(&self, a: &T, b: &T, k: usize) -> Ordering;
+}
+
+impl<T> SortKeys<T> for () {
+    const LEN: usize = 0;
+    fn compare_first(&self, _a: &T, _b: &T, _k: usize) -> Ordering { Ordering::Equal }
+}
+
+impl<T,K,R> SortKeys<T> for (K,R) where K: Compare<T>, R: SortKeys<T> {
+    const LEN: usize = 1 + R::LEN;
+    fn compare_first(&self, a: &T, b: &T, k: usize) -> Ordering {
+        if k == 0 { return Ordering::Equal };
+        self.0.compare(a, b).then_with(|| self.1.compare_first(a, b, k-1))
+    }
+}
+
+/// Appending the next key at the end of the list of keys
+pub trait Append<K> {
+    /// The longer list
+    type Output;
+    /// The list with k appended
+    fn append(self, k: K) -> Self::Output;
+}
+
+impl<K> Append<K> for () {
+    type Output = (K,());
+    fn append(self, k: K) -> (K,()) { (k,()) }
+}
+
+impl<K,H,R> Append<K> for (H,R) where R: Append<K> {
+    type Output = (H,R::Output);
+    fn append(self, k: K) -> Self::Output { (self.0, self.1.append(k)) }
+}
+
+/// Tuple of the values of the first keys of a SortSpec, e.g. `("sales",70)`,
+/// each of the type of its key, which is checked at compile time
+pub trait KeyPrefix<T,Keys> {
+    /// Ordering of the first keys of a relative to the values of self
+    fn compare_prefix(&self, keys: &Keys, a: &T) -> Ordering;
+}
+
+impl<T,Keys> KeyPrefix<T,Keys> for () {
+    fn compare_prefix(&self, _keys: &Keys, _a: &T) -> Ordering { Ordering::Equal }
+}
+
+impl<T,V1,K1,R> KeyPrefix<T,(K1,R)> for (V1,) where K1: KeyValue<T,V1> {
+    fn compare_prefix(&self, keys: &(K1,R), a: &T) -> Ordering { keys.0.compare_value(a, &self.0) }
+}
+
+impl<T,V1,V2,K1,K2,R> KeyPrefix<T,(K1,(K2,R))> for (V1,V2)
+    where K1: KeyValue<T,V1>, K2: KeyValue<T,V2> {
+    fn compare_prefix(&self, keys: &(K1,(K2,R)), a: &T) -> Ordering {
+        keys.0.compare_value(a, &self.0).then_with(|| keys.1.0.compare_value(a, &self.1))
+    }
+}
+
+impl<T,V1,V2,V3,K1,K2,K3,R> KeyPrefix<T,(K1,(K2,(K3,R)))> for (V1,V2,V3)
+    where K1: KeyValue<T,V1>, K2: KeyValue<T,V2>, K3: KeyValue<T,V3> {
+    fn compare_prefix(&self, keys: &(K1,(K2,(K3,R))), a: &T) -> Ordering {
+        keys.0.compare_value(a, &self.0).then_with(|| keys.1.0.compare_value(a, &self.1))
+            .then_with(|| keys.1.1.0.compare_value(a, &self.2))
+    }
+}
+
+impl<T,V1,V2,V3,V4,K1,K2,K3,K4,R> KeyPrefix<T,(K1,(K2,(K3,(K4,R))))> for (V1,V2,V3,V4)
+    where K1: KeyValue<T,V1>, K2: KeyValue<T,V2>, K3: KeyValue<T,V3>, K4: KeyValue<T,V4> {
+    fn compare_prefix(&self, keys: &(K1,(K2,(K3,(K4,R)))), a: &T) -> Ordering {
+        keys.0.compare_value(a, &self.0).then_with(|| keys.1.0.compare_value(a, &self.1))
+            .then_with(|| keys.1.1.0.compare_value(a, &self.2)).then_with(|| keys.1.1.1.0.compare_value(a, &self.3))
+    }
+}
+
+/// Lexicographic ordering by several keys, each ascending or descending.
+/// Later keys break the ties of the earlier ones.
+/// It is `Send`, `Sync` and `Clone` when its key closures are.
+pub struct SortSpec<T,Keys> {
+    keys: Keys,
+    item: PhantomData<fn(&T)>
+}
+
+impl<T,Keys: Clone> Clone for SortSpec<T,Keys> {
+    fn clone(&self) -> Self { SortSpec{ keys:self.keys.clone(), item:PhantomData } }
+}
+
+impl<T> Default for SortSpec<T,()> {
+    fn default() -> Self { SortSpec{ keys:(), item:PhantomData } }
+}
+
+impl<T> SortSpec<T,()> {
+    /// New specification without any keys (all items equal)
+    pub fn new() -> Self { Self::default() }
+}
+
+impl<T,Keys> SortSpec<T,Keys> where Keys: SortKeys<T> {
+    /// Appends the next key, extracted by key_fn, in asc or desc order
+    pub fn then<K,F>(self, key_fn: F, asc: bool) -> SortSpec<T,Keys::Output>
+        where K: PartialOrd, F: Fn(&T) -> K, Keys: Append<Key<F>> {
+        SortSpec{ keys:self.keys.append(Key{ key_fn, asc }), item:PhantomData }
+    }
+
+    /// Appends the next key, borrowed from the item by key_fn, e.g. `|e:&Employee| e.name.as_str()`,
+    /// in asc or desc order. Its prefix values are references, e.g. `&str`.
+    pub fn then_ref<K,F>(self, key_fn: F, asc: bool) -> SortSpec<T,Keys::Output>
+        where K: PartialOrd+?Sized, F: for<'a> Fn(&'a T) -> &'a K, Keys: Append<KeyRef<F>> {
+        SortSpec{ keys:self.keys.append(KeyRef{ key_fn, asc }), item:PhantomData }
+    }
+
+    /// Number of keys
+    pub fn len(&self) -> usize { Keys::LEN }
+
+    /// True when there are no keys
+    pub fn is_empty(&self) -> bool { Keys::LEN == 0 }
+
+    /// Comparison of a and b by the first k keys only
+    pub fn compare_prefix(&self, a: &T, b: &T, k: usize) -> Ordering {
+        self.keys.compare_first(a, b, k)
+    }
+
+    /// Comparison of the first keys of a with the tuple of key values prefix, in their order
+    pub fn compare_keys(&self, a: &T, prefix: &impl KeyPrefix<T,Keys>) -> Ordering {
+        prefix.compare_prefix(&self.keys, a)
+    }
+}
+
+impl<T,Keys> Compare<T> for SortSpec<T,Keys> where Keys: SortKeys<T> {
+    fn compare(&self, a: &T, b: &T) -> Ordering { self.keys.compare_first(a, b, Keys::LEN) }
+}
+
+/// First i in 0..n for which pred(i) is false, pred being true for some prefix of 0..n
+fn partition(n: usize, pred: impl Fn(usize) -> bool) -> usize {
+    let (mut lo, mut hi) = (0, n);
+    while lo < hi {
+        let mid = lo + (hi - lo)/2;
+        if pred(mid) { lo = mid + 1 } else { hi = mid }
+    }
+    lo
+}
+
+/// Set of items of any type T, ordered by order: C (a key or a comparator) instead of T's own `PartialOrd`.
+/// Its fields have the same meaning as those of `Set`.
+#[derive(Clone)]
+pub struct SetBy<T,C> {
+    /// type of the set
+    pub stype: SType,
+    /// order: ascending (true), descending (false)
+    pub ascending: bool,
+    /// data Vec
+    pub data: Vec<T>,
+    /// index: sort index of Indexed sets, ranks of Ranked sets, empty otherwise
+    pub index: Permutation,
+    /// the ordering of the items
+    pub order: C
+}
+
+impl<T:Clone> Set<T> {
+    /// Initialiser - creates a new SetBy of set_type from data d, in asc order,
+    /// ordering, searching and comparing its items only by their keys: key_fn(&item)
+    pub fn new_by_key<K,F>(set_type: SType, d: &[T], asc: bool, key_fn: F) -> SetBy<T,ByKey<F>>
+        where K: PartialOrd, F: Fn(&T) -> K + Clone {
+        SetBy::new(set_type, d, asc, ByKey(key_fn))
+    }
+
+    /// Initialiser - creates a new SetBy of set_type from data d, in asc order,
+    /// ordering, searching and comparing its items only by the comparator cmp
+    pub fn new_by<F>(set_type: SType, d: &[T], asc: bool, cmp: F) -> SetBy<T,ByCmp<F>>
+        where F: Fn(&T,&T) -> Ordering + Clone {
+        SetBy::new(set_type, d, asc, ByCmp(cmp))
+    }
+
+    /// Initialiser - creates a new SetBy of set_type from data d,
+    /// ordered lexicographically by the keys of spec, in their own directions
+    pub fn new_by_spec<Keys>(set_type: SType, d: &[T], spec: SortSpec<T,Keys>) -> SetBy<T,SortSpec<T,Keys>>
+        where Keys: SortKeys<T>+Clone {
+        SetBy::new(set_type, d, true, spec)
+    }
+}
+
+impl<T,C> SetBy<T,C> where T: Clone, C: Compare<T>+Clone {
+
+    /// all in one Initialiser creates a new SetBy
+    /// of set_type, from slice d, in asc order
+    pub fn new(set_type: SType, d: &[T], asc: bool, order: C) -> Self {
+        let unordered = SetBy{ stype:SType::Unordered, ascending:true, data:d.to_vec(),
+            index:Permutation(Vec::new()), order };
+        match set_type {
+            _ if d.is_empty() => unordered.with(SType::Empty, true, Vec::new(), Vec::new()),
+            SType::Empty => unordered.with(SType::Empty, true, Vec::new(), Vec::new()),
+            SType::Unordered => unordered,
+            SType::Ordered => unordered.to_ordered(asc),
+            SType::Indexed => unordered.to_indexed(asc),
+            SType::Ranked => unordered.to_ranked(asc)
+        }
+    }
+
+    /// New SetBy with the same order as self
+    fn with(&self, stype: SType, ascending: bool, data: Vec<T>, index: Vec<usize>) -> Self {
+        SetBy{ stype, ascending, data, index:Permutation(index), order:self.order.clone() }
+    }
+
+    /// New SetBy of the same type, direction and order as self, from data d
+    pub fn new_same(&self, d: &[T]) -> Self {
+        SetBy::new(self.stype, d, self.ascending, self.order.clone())
+    }
+
+    /// Number of items
+    pub fn len(&self) -> usize { self.data.len() }
+
+    /// True when there are no items
+    pub fn is_empty(&self) -> bool { self.data.is_empty() }
+
+    /// Drops the order, returning the plain Set
+    pub fn into_set(self) -> Set<T> {
+        Set{ stype:self.stype, ascending:self.ascending, data:self.data, index:self.index }
+    }
+
+    /// Stable ascending sort index of the data
+    fn sortindex(&self) -> Vec<usize> {
+        let mut idx = trivindex(true, self.data.len());
+        idx.sort_by(|&a,&b| self.order.compare(&self.data[a], &self.data[b]));
+        idx
+    }
+
+    /// Permutation listing the subscripts of data in asc or desc sorted order
+    pub fn sorted(&self, asc: bool) -> Permutation {
+        let (sorted, sorted_asc) = match self.stype {
+            SType::Empty => return Permutation(Vec::new()),
+            SType::Unordered => (Permutation(self.sortindex()), true),
+            SType::Ordered => (Permutation::identity(self.data.len()), self.ascending),
+            SType::Indexed => (self.index.clone(), self.ascending),
+            SType::Ranked => (self.index.inverse(), self.ascending)
+        };
+        if sorted_asc == asc { sorted } else { sorted.reverse() }
+    }
+
+    /// Converter - to SType::Unordered
+    pub fn to_unordered(&self) -> Self {
+        match self.stype {
+            SType::Empty => self.clone(),
+            _ => self.with(SType::Unordered, self.ascending, self.data.clone(), Vec::new())
+        }
+    }
+
+    /// Converts any SetBy type to ordered
+    pub fn to_ordered(&self, asc: bool) -> Self {
+        match self.stype {
+            SType::Empty => self.clone(),
+            _ => self.with(SType::Ordered, asc, self.sorted(asc).apply(&self.data), Vec::new())
+        }
+    }
+
+    /// Converts any SetBy type to indexed
+    pub fn to_indexed(&self, asc: bool) -> Self {
+        match self.stype {
+            SType::Empty => self.clone(),
+            _ => self.with(SType::Indexed, asc, self.data.clone(), self.sorted(asc).into_vec())
+        }
+    }
+
+    /// Converts any SetBy type to ranked
+    pub fn to_ranked(&self, asc: bool) -> Self {
+        match self.stype {
+            SType::Empty => self.clone(),
+            _ => self.with(SType::Ranked, asc, self.data.clone(), self.sorted(asc).inverse().into_vec())
+        }
+    }
+
+    /// General converter: s -> SetBy of the same type and order as self
+    pub fn to_same(&self, s: &Self) -> Self {
+        match self.stype {
+            SType::Empty => self.clone(),
+            SType::Unordered => s.to_unordered(),
+            SType::Ordered => s.to_ordered(self.ascending),
+            SType::Indexed => s.to_indexed(self.ascending),
+            SType::Ranked => s.to_ranked(self.ascending)
+        }
+    }
+
+    /// SetBy of the same type and order as self, made from ascending sorted data v
+    fn same_sorted(&self, mut v: Vec<T>) -> Self {
+        let n = v.len();
+        if n == 0 { return self.with(SType::Empty, true, v, Vec::new()) };
+        match self.stype {
+            SType::Empty | SType::Ordered => {
+                if !self.ascending { v.reverse() };
+                self.with(SType::Ordered, self.ascending, v, Vec::new()) },
+            SType::Unordered => self.with(SType::Unordered, self.ascending, v, Vec::new()),
+            SType::Indexed | SType::Ranked => self.with(self.stype, self.ascending, v, trivindex(self.ascending, n))
+        }
+    }
+
+    /// Range of the positions in the sort order of self, `sorted(self.ascending)`, of the items
+    /// for which probe(item) returns Equal, by binary search.
+    /// Probe compares an item to the sought target in ascending terms.
+    /// None for Empty and Unordered sets, which have no sort order.
+    pub fn range_by(&self, probe: impl Fn(&T) -> Ordering) -> Option<Range<usize>> {
+        match self.stype {
+            SType::Empty | SType::Unordered => None,
+            _ => Some(self.locate(probe).0)
+        }
+    }
+
+    /// Range of the positions, in the sort order of self, of the items for which
+    /// probe(item) returns Equal. Probe compares an item to the sought target in ascending terms.
+    /// Also returns the sort index used. Not applicable to Empty and Unordered sets.
+    fn locate(&self, probe: impl Fn(&T) -> Ordering) -> (Range<usize>, Option<Vec<usize>>) {
+        let dir = |o: Ordering| if self.ascending { o } else { o.reverse() };
+        let n = self.data.len();
+        match self.stype {
+            SType::Ordered => {
+                let at = |i: usize| dir(probe(&self.data[i]));
+                (partition(n, |i| at(i) == Ordering::Less)..partition(n, |i| at(i) != Ordering::Greater), None) },
+            SType::Indexed => {
+                let at = |i: usize| dir(probe(&self.data[self.index[i]]));
+                (partition(n, |i| at(i) == Ordering::Less)..partition(n, |i| at(i) != Ordering::Greater), None) },
+            _ => {
+                let sortindex = self.index.inverse().into_vec();
+                let at = |i: usize| dir(probe(&self.data[sortindex[i]]));
+                (partition(n, |i| at(i) == Ordering::Less)..partition(n, |i| at(i) != Ordering::Greater), Some(sortindex)) }
+        }
+    }
+
+    /// Data subscripts of all the items for which probe(item) returns Equal,
+    /// in the sort order of self (in data order for Unordered).
+    /// Probe compares an item to the sought target in ascending terms.
+    pub fn search_all_by(&self, probe: impl Fn(&T) -> Ordering) -> Vec<usize> {
+        match self.stype {
+            SType::Empty => Vec::new(),
+            SType::Unordered => (0..self.data.len()).filter(|&i| probe(&self.data[i]) == Ordering::Equal).collect(),
+            SType::Ordered => self.locate(probe).0.collect(),
+            SType::Indexed => self.index[self.locate(probe).0].to_vec(),
+            SType::Ranked => { let (r,si) = self.locate(probe); si.unwrap_or_default()[r].to_vec() }
+        }
+    }
+
+    /// Data subscript of the first item for which probe(item) returns Equal, or None
+    pub fn search_by(&self, probe: impl Fn(&T) -> Ordering) -> Option<usize> {
+        match self.stype {
+            SType::Empty => None,
+            SType::Unordered => self.data.iter().position(|x| probe(x) == Ordering::Equal),
+            _ => self.search_all_by(probe).first().copied()
+        }
+    }
+
+    /// Search self for an item equal to m in the order of self.
+    /// Returns the subscript of the first one or None
+    pub fn search(&self, m: &T) -> Option<usize> {
+        self.search_by(|x| self.order.compare(x, m))
+    }
+
+    /// True if m is a member of self
+    pub fn member(&self, m: &T) -> bool {
+        self.search(m).is_some()
+    }
+
+    /// Inserts an item, after any equal ones
+    pub fn minsert(&mut self, item: T) {
+        match self.stype {
+            SType::Empty => {
+                self.stype = SType::Ordered;
+                self.data.push(item); },
+            SType::Unordered => self.data.push(item),
+            SType::Ordered => {
+                let pos = self.locate(|x| self.order.compare(x, &item)).0.end;
+                self.data.insert(pos, item); },
+            SType::Indexed => {
+                let pos = self.locate(|x| self.order.compare(x, &item)).0.end;
+                self.data.push(item);
+                self.index.0.insert(pos, self.data.len()-1); },
+            SType::Ranked => {
+                let pos = self.locate(|x| self.order.compare(x, &item)).0.end;
+                self.data.push(item);
+                for rank in &mut self.index.0 { if *rank >= pos { *rank += 1 } };
+                self.index.0.push(pos); }
+        }
+    }
+
+    /// Deletes the first item equal to item.
+    /// Returns false if item not found
+    pub fn mdelete(&mut self, item: &T) -> bool {
+        let Some(sub) = self.search(item) else { return false };
+        match self.stype {
+            SType::Empty => return false,
+            SType::Unordered => { self.data.swap_remove(sub); },
+            SType::Ordered => { self.data.remove(sub); },
+            SType::Indexed | SType::Ranked => {
+                let mut sortindex = self.sorted(self.ascending).into_vec();
+                self.data.remove(sub);
+                sortindex.retain(|&i| i != sub);
+                for i in &mut sortindex { if *i > sub { *i -= 1 } };
+                self.index = if let SType::Indexed = self.stype { Permutation(sortindex) }
+                    else { Permutation(sortindex).inverse() }; }
+        }
+        if self.data.is_empty() { *self = self.with(SType::Empty, true, Vec::new(), Vec::new()) };
+        true
+    }
+
+    /// Inserts an item into a copy of self
+    pub fn insert(&self, item: T) -> Self {
+        let mut scopy = self.clone();
+        scopy.minsert(item);
+        scopy
+    }
+
+    /// Deletes an item from a copy of self
+    pub fn delete(&self, item: &T) -> Self {
+        let mut scopy = self.clone();
+        scopy.mdelete(item);
+        scopy
+    }
+
+    /// Reverses the order of self
+    pub fn mreverse(&mut self) {
+        match self.stype {
+            SType::Empty => (),
+            SType::Unordered => self.data.reverse(),
+            SType::Ordered => { self.ascending = !self.ascending; self.data.reverse() },
+            SType::Indexed => { self.ascending = !self.ascending; self.index.0.reverse() },
+            SType::Ranked => { self.ascending = !self.ascending; self.index = self.index.complement() }
+        }
+    }
+
+    /// Data of self sorted in ascending order
+    fn ascending_data(&self) -> Vec<T> {
+        self.sorted(true).apply(&self.data)
+    }
+
+    /// Deletes all repetitions (equal in the order of self), leaving the data sorted
+    pub fn mnonrepeat(&mut self) {
+        if self.data.len() < 2 { return };
+        let mut v = self.ascending_data();
+        v.dedup_by(|a,b| self.order.compare(a, b) == Ordering::Equal);
+        *self = self.same_sorted(v);
+    }
+
+    /// Merges ascending a and b into ascending result, as selected by keep:
+    /// which of (a only, both, b only) items to keep
+    fn combine(&self, a: &[T], b: &[T], keep: (bool,bool,bool)) -> Vec<T> {
+        let mut res = Vec::with_capacity(a.len() + b.len());
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            match self.order.compare(&a[i], &b[j]) {
+                Ordering::Less => { if keep.0 { res.push(a[i].clone()) }; i += 1 },
+                Ordering::Greater => { if keep.2 { res.push(b[j].clone()) }; j += 1 },
+                Ordering::Equal => {
+                    if keep.1 { res.push(a[i].clone()) };
+                    if keep.0 && keep.2 { res.push(b[j].clone()) };
+                    i += 1; j += 1 }
+            }
+        }
+        if keep.0 { res.extend_from_slice(&a[i..]) };
+        if keep.2 { res.extend_from_slice(&b[j..]) };
+        res
+    }
+
+    /// Union of self and s, in the type and order of self
+    pub fn union(&self, s: &Self) -> Self {
+        self.same_sorted(self.combine(&self.ascending_data(), &s.ascending_data(), (true,true,true)))
+    }
+
+    /// Intersection of self and s, in the type and order of self
+    pub fn intersection(&self, s: &Self) -> Self {
+        self.same_sorted(self.combine(&self.ascending_data(), &s.ascending_data(), (false,true,false)))
+    }
+
+    /// Complement of s in self (i.e. self - s), in the type and order of self
+    pub fn difference(&self, s: &Self) -> Self {
+        self.same_sorted(self.combine(&self.ascending_data(), &s.ascending_data(), (true,false,false)))
+    }
+}
+
+impl<T,K,F> SetBy<T,ByKey<F>> where T: Clone, K: PartialOrd, F: Fn(&T) -> K + Clone {
+    /// Data subscript of the first item with the given key, or None
+    pub fn search_key(&self, key: &K) -> Option<usize> {
+        self.search_by(|x| (self.order.0)(x).partial_cmp(key).expect("search_key comparison failure"))
+    }
+
+    /// The first item with the given key, or None
+    pub fn get(&self, key: &K) -> Option<&T> {
+        self.search_key(key).map(|i| &self.data[i])
+    }
+}
+
+impl<T,Keys> SetBy<T,SortSpec<T,Keys>> where T: Clone, Keys: SortKeys<T>+Clone {
+    /// Range of the positions in the sort order of self (see `range_by`) of the items
+    /// whose first keys equal the tuple of key values prefix, e.g. `&("sales",)`.
+    /// None for Empty and Unordered sets.
+    pub fn range_prefix(&self, prefix: &impl KeyPrefix<T,Keys>) -> Option<Range<usize>> {
+        self.range_by(|x| self.order.compare_keys(x, prefix))
+    }
+
+    /// Data subscripts of the items whose first keys equal the tuple of key values prefix,
+    /// in the sort order of self (in data order for Unordered)
+    pub fn search_prefix(&self, prefix: &impl KeyPrefix<T,Keys>) -> Vec<usize> {
+        self.search_all_by(|x| self.order.compare_keys(x, prefix))
+    }
+}
@@ -1,11 +1,27 @@
-use crate::{SType,Set,MutSetOps,trivindex};
-use indxvec::{MinMax,Indices,Vecops};
+use crate::prelude::*;
+use crate::{SType,Set,MutSetOps,Permutation,trivindex,ranking,ranking::RankMethod,topk};
+use crate::compat::{MinMax,Indices,Vecops};
 
 /// Associated functions for conversions and self operations returning Set<T> = Self
 impl<T> Set<T> where T: Copy+PartialOrd+Default {
 
     /// Associated constant EMPTYSET, unique for each concrete end-type T
-    pub const EMPTYSET:Set<T> = Set{ stype:SType::Empty, ascending:true, data:Vec::new(), index:Vec::new() };
+    pub const EMPTYSET:Set<T> = Set{ stype:SType::Empty, ascending:true, data:Vec::new(), index:Permutation(Vec::new()) };
+
+    /// The SType that the operations dispatch on: Empty for any set without data.
+    /// Sets made `with_capacity`, cleared or typed keep their declared stype when empty,
+    /// this is the one place where they are told apart from the sets with data.
+    pub(crate) fn kind(&self) -> SType {
+        if self.data.is_empty() { SType::Empty } else { self.stype }
+    }
+
+    /// Set without data of set_type and asc order, or EMPTYSET when self is not typed
+    fn nodata(&self, set_type: SType, asc: bool) -> Self {
+        match self.stype {
+            SType::Empty => Set::EMPTYSET,
+            _ => Self{ stype:set_type, ascending:asc, data:Vec::new(), index:Permutation(Vec::new()) }
+        }
+    }
 
     /// all in one Initialiser creates a new Set
     /// of self_type, from slice d, in asc order 
@@ -13,12 +29,12 @@ impl<T> Set<T> where T: Copy+PartialOrd+Default {
         if d.is_empty() { return Set::EMPTYSET }; // no data
         match set_type {
             SType::Empty => Set::EMPTYSET, // empty self specified
-            SType::Unordered => Set{ stype:SType::Unordered, ascending:true, data:d.to_vec(), index:Vec::new() }, 
-            SType::Ordered => Set{ stype:SType::Ordered, ascending:asc, data:d.sortm(asc), index:Vec::new() },
+            SType::Unordered => Set{ stype:SType::Unordered, ascending:true, data:d.to_vec(), index:Permutation(Vec::new()) }, 
+            SType::Ordered => Set{ stype:SType::Ordered, ascending:asc, data:d.sortm(asc), index:Permutation(Vec::new()) },
             SType::Indexed =>  Set{ stype:SType::Indexed, ascending:asc, data:d.to_vec(), 
-                index: if asc { d.mergesort_indexed() } else { d.mergesort_indexed().revs() } },
+                index: Permutation(if asc { d.mergesort_indexed() } else { d.mergesort_indexed().revs() }) },
             SType::Ranked => Set{ stype:SType::Ranked, ascending:asc, data:d.to_vec(), 
-                index: if asc { d.mergesort_indexed().invindex() } else { d.mergesort_indexed().revs().invindex() } } }
+                index: Permutation(if asc { d.mergesort_indexed().invindex() } else { d.mergesort_indexed().revs().invindex() }) } }
     }
 
     /// Creates a new empty Set
@@ -27,14 +43,14 @@ impl<T> Set<T> where T: Copy+PartialOrd+Default {
     /// Initialiser - creates a new SType::Unordered Set from data
     pub fn new_unordered(d: &[T]) -> Self {  
         if !d.is_empty() { // have some data
-            Set{ stype:SType::Unordered, ascending:true, data:d.to_vec(), index:Vec::new() } } 
+            Set{ stype:SType::Unordered, ascending:true, data:d.to_vec(), index:Permutation(Vec::new()) } } 
         else { Set::EMPTYSET } 
     }
 
     /// Initialiser - creates a new SType::Ordered Set in asc order from data 
     pub fn new_ordered(d: &[T], asc:bool) -> Self {  
         if !d.is_empty() { // have some data
-            Set{ stype:SType::Ordered, ascending:asc, data:d.sortm(asc), index:Vec::new() } }
+            Set{ stype:SType::Ordered, ascending:asc, data:d.sortm(asc), index:Permutation(Vec::new()) } }
         else { Set::EMPTYSET } 
     }
 
@@ -42,7 +58,7 @@ impl<T> Set<T> where T: Copy+PartialOrd+Default {
     pub fn new_indexed(d: &[T], asc:bool) -> Self {  
         if !d.is_empty() { // have some data
             Set{ stype:SType::Indexed, ascending:asc, data:d.to_vec(), 
-                index: if asc { d.mergesort_indexed() } else { d.mergesort_indexed().revs() } } }
+                index: Permutation(if asc { d.mergesort_indexed() } else { d.mergesort_indexed().revs() }) } }
         else { Set::EMPTYSET } 
     }
 
@@ -50,7 +66,7 @@ impl<T> Set<T> where T: Copy+PartialOrd+Default {
     pub fn new_ranked(d: &[T], asc:bool) -> Self {  
         if !d.is_empty() { // have some data
             Set{ stype:SType::Ranked, ascending:asc, data:d.to_vec(), 
-                index: if asc { d.mergesort_indexed().invindex() } else { d.mergesort_indexed().revs().invindex() } } }
+                index: Permutation(if asc { d.mergesort_indexed().invindex() } else { d.mergesort_indexed().revs().invindex() }) } }
         else { Set::EMPTYSET } 
     }
 
@@ -60,7 +76,7 @@ impl<T> Set<T> where T: Copy+PartialOrd+Default {
         match self.stype {
             SType::Empty => Set::EMPTYSET, // no op 
             // ascending field has no meaning for unordered, so just inherit it
-            _ => Self{ stype:SType::Unordered, ascending:self.ascending, data:self.data.clone(), index:Vec::new() }
+            _ => Self{ stype:SType::Unordered, ascending:self.ascending, data:self.data.clone(), index:Permutation(Vec::new()) }
         }
     }
 
@@ -68,47 +84,47 @@ impl<T> Set<T> where T: Copy+PartialOrd+Default {
     pub fn to_ordered(&self, asc:bool) -> Self {
         match self.stype {
             SType::Empty => Set::EMPTYSET, 
-            SType::Unordered => Self{ stype:SType::Ordered, ascending:asc, data:self.data.sortm(asc), index:Vec::new()},
+            SType::Unordered => Self{ stype:SType::Ordered, ascending:asc, data:self.data.sortm(asc), index:Permutation(Vec::new())},
             SType::Ordered => if self.ascending == asc { self.clone() } // just a copy
-                else { Self{ stype:SType::Ordered, ascending:asc, data:self.data.revs(), index:Vec::new() } },
+                else { Self{ stype:SType::Ordered, ascending:asc, data:self.data.revs(), index:Permutation(Vec::new()) } },
             SType::Indexed => Self{ stype:SType::Ordered, ascending:asc, 
-                data:self.index.unindex(&self.data, self.ascending == asc), index:Vec::new() },
+                data:self.index.unindex(&self.data, self.ascending == asc), index:Permutation(Vec::new()) },
             SType::Ranked => Self{ stype:SType::Ordered, ascending:asc, 
-                data:self.index.invindex().unindex(&self.data, self.ascending == asc), index:Vec::new() },
+                data:self.index.invindex().unindex(&self.data, self.ascending == asc), index:Permutation(Vec::new()) },
         }    
     }
 
     /// Converts any Set type to indexed
     pub fn to_indexed(&self,asc:bool) -> Self {
-        match self.stype {
-            SType::Empty => Set::EMPTYSET,
+        match self.kind() {
+            SType::Empty => self.nodata(SType::Indexed, asc),
             SType::Unordered => Self{ stype:SType::Indexed, ascending:asc, data:self.data.clone(), 
-                index: if asc {self.data.mergesort_indexed()} else {self.data.mergesort_indexed().revs()} },
+                index: Permutation(if asc {self.data.mergesort_indexed()} else {self.data.mergesort_indexed().revs()}) },
             SType::Ordered => Self{ stype:SType::Indexed, ascending:asc, data:self.data.clone(), 
-                index: trivindex(self.ascending == asc,self.data.len()) },
+                index: Permutation(trivindex(self.ascending == asc,self.data.len())) },
             SType::Indexed =>  if self.ascending == asc { self.clone() } // no op
                 else { Self{ stype:SType::Indexed, ascending:asc, data:self.data.clone(),
-                    index: self.index.revs() } },
+                    index: self.index.reverse() } },
             SType::Ranked => Self{ stype:SType::Indexed, ascending:asc, data:self.data.clone(),             
-                index: if self.ascending == asc {self.index.invindex()} else {self.index.invindex().revs()}}
+                index: if self.ascending == asc {self.index.inverse()} else {self.index.inverse().reverse()}}
         }    
     }
 
     /// Converts any Set type to ranked
     pub fn to_ranked(&self,asc:bool) -> Self {
-        match self.stype {
-            SType::Empty => Set::EMPTYSET,
+        match self.kind() {
+            SType::Empty => self.nodata(SType::Ranked, asc),
             SType::Unordered => Self{ stype:SType::Ranked, ascending:asc, data:self.data.clone(), 
-                index: if asc {self.data.mergesort_indexed().invindex()} 
-                    else {self.data.mergesort_indexed().revs().invindex()} },
+                index: Permutation(if asc {self.data.mergesort_indexed().invindex()} 
+                    else {self.data.mergesort_indexed().revs().invindex()}) },
             SType::Ordered => Self{ stype:SType::Ranked, ascending:asc, data:self.data.clone(), 
-                index: trivindex(self.ascending == asc,self.data.len()) },
+                index: Permutation(trivindex(self.ascending == asc,self.data.len())) },
             SType::Indexed => Self{ stype:SType::Ranked, ascending:asc, data:self.data.clone(),             
-                index: if self.ascending == asc {self.index.invindex()} 
-                    else {self.index.revs().invindex()}}, 
+                index: if self.ascending == asc {self.index.inverse()} 
+                    else {self.index.reverse().inverse()}}, 
             SType::Ranked => if self.ascending == asc { self.clone() } // no op
                 else { Self{ stype:SType::Ranked, ascending:asc, data:self.data.clone(),
-                    index: {self.index.complindex()} } }
+                    index: self.index.complement() } }
         }    
     }
 
@@ -125,49 +141,49 @@ impl<T> Set<T> where T: Copy+PartialOrd+Default {
     }       
 
     /// Inserts an item of the same end-type to self
-    pub fn insert(&self, item:T) -> Self {
+    pub fn insert(&self, item:T) -> Self where T: 'static {
         let mut scopy = self.clone();
         scopy.minsert(item);
         scopy 
     }
 
     /// Deletes an item of the same end-type from self
-    pub fn delete(&self, item:T) -> Self {
+    pub fn delete(&self, item:T) -> Self where T: 'static {
         let mut scopy = self.clone();
         if scopy.mdelete(item) { scopy } else { self.clone() }
     }    
 
     /// Reverses a vec by iterating over only half of its length
     /// and swapping the items
-    pub fn reverse(&self) -> Self { 
+    pub fn reverse(&self) -> Self where T: 'static { 
         let mut scopy = self.clone();
         scopy.mreverse();
         scopy
     }
  
     /// Deletes any repetitions
-    pub fn nonrepeat(&self) -> Self { 
+    pub fn nonrepeat(&self) -> Self where T: 'static { 
         let mut scopy =  self.clone();
         scopy.mnonrepeat();
         scopy
     }
 
     /// Union of two selfs  
-    pub fn union(&self, s: &Self) -> Self {
+    pub fn union(&self, s: &Self) -> Self where T: 'static {
         let mut scopy =  self.clone();
         scopy.munion(s);
         scopy
     }
     
     /// Intersection of two selfs
-    pub fn intersection(&self, s: &Self) -> Self {
+    pub fn intersection(&self, s: &Self) -> Self where T: 'static {
         let mut scopy = self.clone();
         scopy.mintersection(s);
         scopy
     }
     
     /// Complement of s in self (i.e. self -= s)
-    pub fn difference(&self, s: &Self) -> Self {
+    pub fn difference(&self, s: &Self) -> Self where T: 'static {
         let mut scopy = self.clone();
         scopy.mdifference(s);
         scopy
@@ -175,51 +191,140 @@ impl<T> Set<T> where T: Copy+PartialOrd+Default {
 
     /// Finds minimum, minimum's first index, maximum, maximum's first index  
     pub fn infsup(&self) -> MinMax<T> where T: Default {
-        match self.stype {
-            SType::Empty => Default::default(),
-            SType::Unordered => self.data.minmax(),  
-            SType::Ordered => {
-                let last = self.data.len()-1;
-                if self.ascending { MinMax{min:self.data[0],minindex:0,max:self.data[last],maxindex:last} }
-                else { MinMax{min:self.data[last],minindex:last,max:self.data[0],maxindex:0} } 
-            },
-            SType::Indexed => {
-                let last = self.data.len()-1;
-                let firstval = self.data[self.index[0]];
-                let lastval = self.data[self.index[last]];
-                if self.ascending { MinMax{min:firstval,minindex:self.index[0],max:lastval,maxindex:self.index[last]} }
-                else { MinMax{min:lastval,minindex:self.index[last],max:firstval,maxindex:self.index[0]} }
-            }, 
-            SType::Ranked => {
-                let last = self.data.len()-1;
-                let si = self.index.invindex(); // ranks -> sort index
-                let firstval = self.data[si[0]];
-                let lastval = self.data[si[last]];
-                    if self.ascending { MinMax{min:firstval,minindex:si[0],max:lastval,maxindex:si[last]} }
-                    else { MinMax{min:lastval,minindex:si[last],max:firstval,maxindex:si[0]} }
-            }
-        }      
+        self.view().infsup()
     }
     
     /// Search a Set self for m.
     /// Returns the subscript of the first m or None   
     pub fn search(&self, m: T) -> Option<usize> { 
-        match self.stype {
-            SType::Empty => None,
-            SType::Unordered => self.data.member(m,true), // from indxvec ,
-            SType::Ordered => { let r = self.data.binsearch(&m);
-                if r.start == r.end { None } else { Some(r.start) } },    
-            SType::Indexed => { let r = self.data.binsearch_indexed(&self.index,&m);
-                if r.start == r.end { None } else { Some(self.index[r.start]) } },    
-            SType::Ranked =>  { let r = self.data.binsearch_indexed(&self.index.invindex(),&m);
-                if r.start == r.end { None } else { Some(self.index[r.start]) }} 
-            }       
+        self.view().search(m)
     }       
     
     /// True if m is a member of the self
     /// Throws away the subscript found by `search`
     pub fn member(&self, m: T) -> bool {
         self.search(m).is_some() 
-    }   
+    }
+
+    /// Exact Jaccard similarity |self ∩ s| / |self ∪ s| of the distinct items.
+    /// Two empty sets are taken to be identical (similarity 1).
+    pub fn jaccard(&self, s: &Self) -> f64 where T: 'static {
+        let a = self.to_ordered(true).nonrepeat();
+        let b = s.to_ordered(true).nonrepeat();
+        let (na, nb) = (a.data.len(), b.data.len());
+        if na == 0 && nb == 0 { return 1_f64 };
+        if na == 0 || nb == 0 { return 0_f64 };
+        let common = a.data.intersect(&b.data).len();
+        common as f64 / (na + nb - common) as f64
+    }
+
+    /// Rank vector of self: its own ranks when Ranked, otherwise
+    /// the ranks of its data in its own order (ascending for Unordered)
+    pub fn rankvec(&self) -> Vec<usize> {
+        match self.stype {
+            SType::Empty => Vec::new(),
+            SType::Ranked => self.index.to_vec(),
+            SType::Unordered => self.to_ranked(true).index.into_vec(),
+            _ => self.to_ranked(self.ascending).index.into_vec()
+        }
+    }
+
+    /// Ranks of the data of self, in its order (ascending for Unordered),
+    /// with the tied items ranked by the given method
+    pub fn ranks(&self, method: RankMethod) -> Vec<f64> {
+        match self.stype {
+            SType::Empty => Vec::new(),
+            SType::Unordered => ranking::tiedranks(&self.data, true, method),
+            _ => ranking::tiedranks(&self.data, self.ascending, method)
+        }
+    }
+
+    /// True when self and s rank their items in the same order (Unordered counting as ascending).
+    /// Rejects Ordered sets, whose sorted data have lost the order of their items.
+    fn samerankorder(&self, s: &Self, caller: &str) -> bool {
+        assert!(!matches!(self.stype,SType::Ordered) && !matches!(s.stype,SType::Ordered),
+            "{caller}: Ordered sets have lost the order of their items, use Unordered, Indexed or Ranked sets");
+        let asc = |set: &Self| set.ascending || matches!(set.stype,SType::Unordered);
+        asc(self) == asc(s)
+    }
 
-}
\ No newline at end of file
+    /// Spearman's rho between self and s, as rankings of the same items by their equally long data.
+    /// Ties are given average ranks. Opposite orders of self and s reverse the sign.
+    /// Panics on Ordered sets, whose sorted data have lost the order of their items.
+    pub fn spearman(&self, s: &Self) -> f64 {
+        let same = self.samerankorder(s, "spearman");
+        let rho = ranking::spearman(&self.data, &s.data);
+        if same { rho } else { -rho }
+    }
+
+    /// Kendall's tau-b between self and s, as rankings of the same items by their equally long data.
+    /// Opposite orders of self and s reverse the sign. Panics on Ordered sets, as `spearman`.
+    pub fn kendall(&self, s: &Self) -> f64 {
+        let same = self.samerankorder(s, "kendall");
+        let tau = ranking::kendall(&self.data, &s.data);
+        if same { tau } else { -tau }
+    }
+
+    /// Kendall tau distance between the rank vectors of self and s
+    pub fn kendall_distance(&self, s: &Self) -> u64 {
+        ranking::kendall_distance(&self.rankvec(), &s.rankvec())
+    }
+
+    /// Spearman's footrule distance between the rank vectors of self and s
+    pub fn footrule(&self, s: &Self) -> u64 {
+        ranking::footrule(&self.rankvec(), &s.rankvec())
+    }
+
+    /// Items of self as a ranked list: in their sort order, when self is ordered, indexed or ranked,
+    /// otherwise in the order of the data
+    pub fn rankedlist(&self) -> Vec<T> {
+        match self.stype {
+            SType::Empty => Vec::new(),
+            SType::Unordered | SType::Ordered => self.data.clone(),
+            _ => self.to_ordered(self.ascending).data
+        }
+    }
+
+    /// Rank-biased overlap of self and s as ranked lists, with persistence p (see `topk::rbo`)
+    pub fn rbo(&self, s: &Self, p: f64) -> f64 {
+        topk::rbo(&self.rankedlist(), &s.rankedlist(), p)
+    }
+
+    /// Extrapolated rank-biased overlap of self and s as ranked lists (see `topk::rbo_ext`)
+    pub fn rbo_ext(&self, s: &Self, p: f64) -> f64 {
+        topk::rbo_ext(&self.rankedlist(), &s.rankedlist(), p)
+    }
+
+    /// Average overlap of the top k items of self and s as ranked lists
+    pub fn average_overlap(&self, s: &Self, k: usize) -> f64 {
+        topk::average_overlap(&self.rankedlist(), &s.rankedlist(), k)
+    }
+
+    /// Permutation listing the subscripts of data in asc or desc sorted order.
+    /// Applied to the data (or to any companion column), it sorts them.
+    pub fn order(&self, asc: bool) -> Permutation {
+        let order = match self.stype {
+            SType::Empty => return Permutation(Vec::new()),
+            SType::Unordered => Permutation(self.data.mergesort_indexed()),
+            SType::Ordered => Permutation(trivindex(self.ascending == asc,self.data.len())),
+            SType::Indexed => self.index.clone(),
+            SType::Ranked => self.index.inverse()
+        };
+        match self.stype {
+            SType::Unordered if !asc => order.reverse(),
+            SType::Indexed | SType::Ranked if self.ascending != asc => order.reverse(),
+            _ => order
+        }
+    }
+
+    /// Copy of companion column col, parallel to the data of self,
+    /// rearranged into the sort order of self (ascending for Unordered)
+    pub fn cosort<U: Clone>(&self, col: &[U]) -> Vec<U> {
+        self.order(self.ascending || matches!(self.stype,SType::Unordered)).apply(col)
+    }
+
+    /// In place version of `cosort`, following the cycles of the order
+    pub fn mcosort<U>(&self, col: &mut [U]) {
+        self.order(self.ascending || matches!(self.stype,SType::Unordered)).apply_mut(col)
+    }
+}
@@ -0,0 +1,181 @@
+use crate::prelude::*;
+use crate::{SType,Set,SetView,Permutation,strategy::is_sorted};
+use alloc::collections::BTreeSet;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+
+/// Common interface of Set, SetView, the std sets and sorted slices.
+/// The algebra treats them as mathematical sets, disregarding any repeated items.
+pub trait SetLike<T> where T: Copy+PartialOrd {
+
+    /// Number of items, including any repeats
+    fn len(&self) -> usize;
+
+    /// True when there are no items
+    fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// True when x is a member
+    fn contains(&self, x: &T) -> bool;
+
+    /// The items in the iteration order of the collection: sorted where it has one
+    fn items(&self) -> Vec<T>;
+
+    /// The distinct items in ascending order
+    fn sorted_items(&self) -> Vec<T> {
+        let mut items = self.items();
+        items.sort_by(|a,b| a.partial_cmp(b).expect("SetLike comparison failure"));
+        items.dedup();
+        items
+    }
+
+    /// Union with other, collected into the chosen output O
+    fn union_into<O: SetOutput<T>>(&self, other: &impl SetLike<T>) -> O {
+        let (a, b) = (self.sorted_items(), other.sorted_items());
+        let mut res = Vec::with_capacity(a.len() + b.len());
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            if a[i] < b[j] { res.push(a[i]); i += 1 }
+            else if b[j] < a[i] { res.push(b[j]); j += 1 }
+            else { res.push(a[i]); i += 1; j += 1 }
+        }
+        res.extend_from_slice(&a[i..]);
+        res.extend_from_slice(&b[j..]);
+        O::from_sorted(res)
+    }
+
+    /// Intersection with other, collected into the chosen output O
+    fn intersection_into<O: SetOutput<T>>(&self, other: &impl SetLike<T>) -> O {
+        let (a, b) = (self.sorted_items(), other.sorted_items());
+        let mut res = Vec::with_capacity(a.len().min(b.len()));
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            if a[i] < b[j] { i += 1 }
+            else if b[j] < a[i] { j += 1 }
+            else { res.push(a[i]); i += 1; j += 1 }
+        }
+        O::from_sorted(res)
+    }
+
+    /// Items of self not in other, collected into the chosen output O
+    fn difference_into<O: SetOutput<T>>(&self, other: &impl SetLike<T>) -> O {
+        let (a, b) = (self.sorted_items(), other.sorted_items());
+        let mut res = Vec::with_capacity(a.len());
+        let mut j = 0;
+        for &x in &a {
+            while j < b.len() && b[j] < x { j += 1 };
+            if j == b.len() || x < b[j] { res.push(x) };
+        }
+        O::from_sorted(res)
+    }
+}
+
+/// Collections that can receive the results of the `SetLike` algebra
+pub trait SetOutput<T> {
+    /// Builds the output from distinct ascending items
+    fn from_sorted(items: Vec<T>) -> Self;
+}
+
+impl<T> SetOutput<T> for Vec<T> {
+    fn from_sorted(items: Vec<T>) -> Self { items }
+}
+
+impl<T: Ord> SetOutput<T> for BTreeSet<T> {
+    fn from_sorted(items: Vec<T>) -> Self { items.into_iter().collect() }
+}
+
+#[cfg(feature = "std")]
+impl<T: Hash+Eq> SetOutput<T> for HashSet<T> {
+    fn from_sorted(items: Vec<T>) -> Self { items.into_iter().collect() }
+}
+
+/// Ascending Ordered Set (`EMPTYSET` when empty)
+impl<T> SetOutput<T> for Set<T> where T: Copy+PartialOrd+Default {
+    fn from_sorted(items: Vec<T>) -> Self {
+        if items.is_empty() { return Set::EMPTYSET };
+        Set{ stype:SType::Ordered, ascending:true, data:items, index:Permutation(Vec::new()) }
+    }
+}
+
+impl<T> SetLike<T> for Set<T> where T: Copy+PartialOrd+Default+'static {
+    fn len(&self) -> usize { self.data.len() }
+    fn contains(&self, x: &T) -> bool { self.member(*x) }
+    fn items(&self) -> Vec<T> { self.view().iter().collect() }
+    fn sorted_items(&self) -> Vec<T> { self.to_ordered(true).nonrepeat().data }
+}
+
+impl<T> SetLike<T> for SetView<'_,T> where T: Copy+PartialOrd+Default {
+    fn len(&self) -> usize { self.data.len() }
+    fn contains(&self, x: &T) -> bool { self.member(*x) }
+    fn items(&self) -> Vec<T> { self.iter().collect() }
+}
+
+impl<T> SetLike<T> for BTreeSet<T> where T: Copy+Ord {
+    fn len(&self) -> usize { self.len() }
+    fn contains(&self, x: &T) -> bool { self.contains(x) }
+    fn items(&self) -> Vec<T> { self.iter().copied().collect() }
+    fn sorted_items(&self) -> Vec<T> { self.items() }
+}
+
+#[cfg(feature = "std")]
+impl<T> SetLike<T> for HashSet<T> where T: Copy+PartialOrd+Hash+Eq {
+    fn len(&self) -> usize { self.len() }
+    fn contains(&self, x: &T) -> bool { self.contains(x) }
+    fn items(&self) -> Vec<T> { self.iter().copied().collect() }
+}
+
+/// Slice known to be sorted in ascending order, searched by binary search
+#[derive(Clone,Copy)]
+pub struct SortedSlice<'a,T>(&'a [T]);
+
+impl<'a,T: PartialOrd> SortedSlice<'a,T> {
+    /// Wraps d, or returns None when it is not in ascending order
+    pub fn new(d: &'a [T]) -> Option<Self> { if is_sorted(d, true) { Some(SortedSlice(d)) } else { None } }
+
+    /// The wrapped slice
+    pub fn as_slice(&self) -> &'a [T] { self.0 }
+}
+
+impl<T> SetLike<T> for SortedSlice<'_,T> where T: Copy+PartialOrd {
+    fn len(&self) -> usize { self.0.len() }
+    fn contains(&self, x: &T) -> bool {
+        let i = self.0.partition_point(|y| y < x);
+        i < self.0.len() && self.0[i] == *x
+    }
+    fn items(&self) -> Vec<T> { self.0.to_vec() }
+    fn sorted_items(&self) -> Vec<T> { let mut items = self.0.to_vec(); items.dedup(); items }
+}
+
+/// BTreeSet -> ascending Ordered Set
+impl<T> From<BTreeSet<T>> for Set<T> where T: Copy+PartialOrd+Default {
+    fn from(s: BTreeSet<T>) -> Self { Set::from_sorted(s.into_iter().collect()) }
+}
+
+/// HashSet -> Unordered Set
+#[cfg(feature = "std")]
+impl<T> From<HashSet<T>> for Set<T> where T: Copy+PartialOrd+Default {
+    fn from(s: HashSet<T>) -> Self {
+        let data: Vec<T> = s.into_iter().collect();
+        Set::new_unordered(&data)
+    }
+}
+
+/// Sorted slice -> ascending Ordered Set, without sorting
+impl<T> From<SortedSlice<'_,T>> for Set<T> where T: Copy+PartialOrd+Default {
+    fn from(s: SortedSlice<'_,T>) -> Self {
+        if s.0.is_empty() { return Set::EMPTYSET };
+        Set{ stype:SType::Ordered, ascending:true, data:s.0.to_vec(), index:Permutation(Vec::new()) }
+    }
+}
+
+/// Set -> BTreeSet of its distinct items
+impl<T> From<Set<T>> for BTreeSet<T> where T: Copy+Ord+Default {
+    fn from(s: Set<T>) -> Self { s.data.into_iter().collect() }
+}
+
+/// Set -> HashSet of its distinct items
+#[cfg(feature = "std")]
+impl<T> From<Set<T>> for HashSet<T> where T: Copy+Hash+Eq+Default {
+    fn from(s: Set<T>) -> Self { s.data.into_iter().collect() }
+}
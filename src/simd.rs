@@ -0,0 +1,440 @@
+
+use crate::prelude::*;
+use crate::compat::Vecops;
+use core::any::{Any,TypeId};
+
+/// Length ratio of the longer to the shorter operand from which the kernels skip.
+/// They skip through the longer operand in bulk, while the shorter one is merged item by item.
+/// Operands of similar lengths are intersected block by block, comparing all the items
+/// of two blocks at once, and their union and difference are merged by scalar code.
+pub const SKIP_RATIO: usize = 2;
+
+mod sealed {
+    /// Only u32 and u64 have kernels
+    pub trait Sealed {}
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+}
+
+/// Intersection, union and difference of ascending sorted slices of u32 or u64,
+/// with the semantics of indxvec `intersect`, `merge` and `diff`.
+/// The `_with` methods run the given kernel, which must be available (see `Kernel::is_available`),
+/// the others the best available one.
+pub trait SortedOps: Sized+sealed::Sealed {
+    /// Items common to a and b, each matching pair yielding one item
+    fn sorted_intersect_with(kernel: Kernel, a: &[Self], b: &[Self]) -> Vec<Self>;
+    /// All the items of a and b, merged in ascending order
+    fn sorted_union_with(kernel: Kernel, a: &[Self], b: &[Self]) -> Vec<Self>;
+    /// Items of a not matched by items of b
+    fn sorted_difference_with(kernel: Kernel, a: &[Self], b: &[Self]) -> Vec<Self>;
+    /// Items common to a and b, by the best kernel
+    fn sorted_intersect(a: &[Self], b: &[Self]) -> Vec<Self> { Self::sorted_intersect_with(Kernel::best(), a, b) }
+    /// All the items of a and b, by the best kernel
+    fn sorted_union(a: &[Self], b: &[Self]) -> Vec<Self> { Self::sorted_union_with(Kernel::best(), a, b) }
+    /// Items of a not matched by items of b, by the best kernel
+    fn sorted_difference(a: &[Self], b: &[Self]) -> Vec<Self> { Self::sorted_difference_with(Kernel::best(), a, b) }
+}
+
+/// The kernels of `SortedOps`
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum Kernel {
+    /// Portable scalar skipping
+    Scalar,
+    /// x86_64 baseline, 128 bit vectors (u64 skips by scalar code)
+    Sse2,
+    /// x86_64, 256 bit vectors
+    Avx2,
+    /// aarch64, 128 bit vectors
+    Neon
+}
+
+impl Kernel {
+    /// Name of the kernel
+    pub fn name(self) -> &'static str {
+        match self { Kernel::Scalar => "scalar", Kernel::Sse2 => "sse2", Kernel::Avx2 => "avx2", Kernel::Neon => "neon" }
+    }
+
+    /// True when the kernel can run on this CPU (on this target, without std)
+    pub fn is_available(self) -> bool {
+        match self {
+            Kernel::Scalar => true,
+            Kernel::Sse2 => cfg!(target_arch = "x86_64"),
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Avx2 => has_avx2(),
+            #[cfg(target_arch = "aarch64")]
+            Kernel::Neon => has_neon(),
+            #[allow(unreachable_patterns)]
+            _ => false
+        }
+    }
+
+    /// The best kernel available
+    pub fn best() -> Self {
+        [Kernel::Avx2, Kernel::Neon, Kernel::Sse2].into_iter().find(|k| k.is_available()).unwrap_or(Kernel::Scalar)
+    }
+}
+
+/// Name of the best kernel available: "avx2" or "sse2" on x86_64, "neon" on aarch64, otherwise "scalar"
+pub fn kernel() -> &'static str { Kernel::best().name() }
+
+/// Runtime detection of avx2 with std, otherwise the compile time target features
+#[cfg(target_arch = "x86_64")]
+fn has_avx2() -> bool {
+    #[cfg(feature = "std")]
+    { std::is_x86_feature_detected!("avx2") }
+    #[cfg(not(feature = "std"))]
+    { cfg!(target_feature = "avx2") }
+}
+
+/// Runtime detection of neon with std, otherwise the compile time target features
+#[cfg(target_arch = "aarch64")]
+fn has_neon() -> bool {
+    #[cfg(feature = "std")]
+    { std::arch::is_aarch64_feature_detected!("neon") }
+    #[cfg(not(feature = "std"))]
+    { cfg!(target_feature = "neon") }
+}
+
+/// Index of the first item of b, at or after j, not less than x
+fn skip_scalar<T: Copy+Ord>(b: &[T], mut j: usize, x: T) -> usize {
+    while j < b.len() && b[j] < x { j += 1 };
+    j
+}
+
+/// Generates the sorted slice operations for one item type, around a function skipping the items of b less than x.
+/// The shorter operand is the first one, except for the difference of a longer a ($diffa).
+macro_rules! sorted_ops {
+    ($(#[$attr:meta])* $t:ty, $skip:path => $inter:ident, $union:ident, $diff:ident, $diffa:ident) => {
+        $(#[$attr])*
+        pub(crate) fn $inter(a: &[$t], b: &[$t]) -> Vec<$t> {
+            let mut res = Vec::with_capacity(a.len().min(b.len()));
+            let mut j = 0;
+            for &x in a {
+                j = $skip(b, j, x);
+                if j == b.len() { break };
+                if b[j] == x { res.push(x); j += 1 };
+            }
+            res
+        }
+        $(#[$attr])*
+        pub(crate) fn $union(a: &[$t], b: &[$t]) -> Vec<$t> {
+            let mut res = Vec::with_capacity(a.len() + b.len());
+            let mut j = 0;
+            for &x in a {
+                let k = $skip(b, j, x);
+                res.extend_from_slice(&b[j..k]);
+                res.push(x);
+                j = k;
+            }
+            res.extend_from_slice(&b[j..]);
+            res
+        }
+        $(#[$attr])*
+        pub(crate) fn $diff(a: &[$t], b: &[$t]) -> Vec<$t> {
+            let mut res = Vec::with_capacity(a.len());
+            let mut j = 0;
+            for &x in a {
+                j = $skip(b, j, x);
+                if j < b.len() && b[j] == x { j += 1 } else { res.push(x) };
+            }
+            res
+        }
+        $(#[$attr])*
+        pub(crate) fn $diffa(a: &[$t], b: &[$t]) -> Vec<$t> {
+            let mut res = Vec::with_capacity(a.len());
+            let mut i = 0;
+            for &y in b {
+                let k = $skip(a, i, y);
+                res.extend_from_slice(&a[i..k]);
+                i = k;
+                if i < a.len() && a[i] == y { i += 1 };
+            }
+            res.extend_from_slice(&a[i..]);
+            res
+        }
+    }
+}
+
+/// Generates the intersection of sorted slices of similar lengths for one item type,
+/// around a function telling whether two blocks of $w items have no item in common.
+/// Disjoint blocks are skipped whole, the others are merged item by item.
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+macro_rules! merge_intersect {
+    ($(#[$attr:meta])* $t:ty, $w:literal, $disjoint:path => $inter:ident) => {
+        $(#[$attr])*
+        pub(crate) fn $inter(a: &[$t], b: &[$t]) -> Vec<$t> {
+            let mut res = Vec::with_capacity(a.len().min(b.len()));
+            let (mut i, mut j) = (0, 0);
+            while i + $w <= a.len() && j + $w <= b.len() {
+                let (ablock, bblock): (&[$t;$w], &[$t;$w]) = (a[i..i+$w].try_into().unwrap(), b[j..j+$w].try_into().unwrap());
+                if $disjoint(ablock, bblock) { // the block with the lesser last item matches nothing further on
+                    if ablock[$w-1] < bblock[$w-1] { i += $w } else { j += $w };
+                    continue;
+                };
+                let (iend, jend) = (i + $w, j + $w);
+                while i < iend && j < jend {
+                    if a[i] < b[j] { i += 1 } else if b[j] < a[i] { j += 1 } else { res.push(a[i]); i += 1; j += 1 };
+                }
+            }
+            while i < a.len() && j < b.len() {
+                if a[i] < b[j] { i += 1 } else if b[j] < a[i] { j += 1 } else { res.push(a[i]); i += 1; j += 1 };
+            }
+            res
+        }
+    }
+}
+
+sorted_ops!(u32, skip_scalar => intersect_u32, union_u32, difference_u32, differencea_u32);
+sorted_ops!(u64, skip_scalar => intersect_u64, union_u64, difference_u64, differencea_u64);
+
+/// Intersection of operands of similar lengths, by indxvec
+fn merge_u32(a: &[u32], b: &[u32]) -> Vec<u32> { a.intersect(b) }
+/// Intersection of operands of similar lengths, by indxvec
+fn merge_u64(a: &[u64], b: &[u64]) -> Vec<u64> { a.intersect(b) }
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use core::arch::x86_64::*;
+    use super::skip_scalar;
+    use crate::prelude::*;
+
+    /// Skips the items of b less than x, eight lanes at a time.
+    /// As b is sorted, the lanes less than x are a prefix of the vector.
+    #[target_feature(enable = "avx2")]
+    fn skip_u32_avx2(b: &[u32], mut j: usize, x: u32) -> usize {
+        let flip = _mm256_set1_epi32(i32::MIN); // unsigned to signed order
+        let xv = _mm256_xor_si256(_mm256_set1_epi32(x as i32), flip);
+        while j + 8 <= b.len() {
+            // SAFETY: j+8 <= b.len(), unaligned load
+            let bv = unsafe { _mm256_loadu_si256(b.as_ptr().add(j) as *const __m256i) };
+            let lt = _mm256_cmpgt_epi32(xv, _mm256_xor_si256(bv, flip));
+            let n = (_mm256_movemask_ps(_mm256_castsi256_ps(lt)) as u32).count_ones() as usize;
+            j += n;
+            if n < 8 { return j };
+        }
+        skip_scalar(b, j, x)
+    }
+
+    /// Skips the items of b less than x, four lanes at a time
+    #[target_feature(enable = "avx2")]
+    fn skip_u64_avx2(b: &[u64], mut j: usize, x: u64) -> usize {
+        let flip = _mm256_set1_epi64x(i64::MIN);
+        let xv = _mm256_xor_si256(_mm256_set1_epi64x(x as i64), flip);
+        while j + 4 <= b.len() {
+            // SAFETY: j+4 <= b.len(), unaligned load
+            let bv = unsafe { _mm256_loadu_si256(b.as_ptr().add(j) as *const __m256i) };
+            let lt = _mm256_cmpgt_epi64(xv, _mm256_xor_si256(bv, flip));
+            let n = (_mm256_movemask_pd(_mm256_castsi256_pd(lt)) as u32).count_ones() as usize;
+            j += n;
+            if n < 4 { return j };
+        }
+        skip_scalar(b, j, x)
+    }
+
+    /// Skips the items of b less than x, four lanes at a time (x86_64 baseline)
+    #[target_feature(enable = "sse2")]
+    fn skip_u32_sse2(b: &[u32], mut j: usize, x: u32) -> usize {
+        let flip = _mm_set1_epi32(i32::MIN);
+        let xv = _mm_xor_si128(_mm_set1_epi32(x as i32), flip);
+        while j + 4 <= b.len() {
+            // SAFETY: j+4 <= b.len(), unaligned load
+            let bv = unsafe { _mm_loadu_si128(b.as_ptr().add(j) as *const __m128i) };
+            let lt = _mm_cmpgt_epi32(xv, _mm_xor_si128(bv, flip));
+            let n = (_mm_movemask_ps(_mm_castsi128_ps(lt)) as u32).count_ones() as usize;
+            j += n;
+            if n < 4 { return j };
+        }
+        skip_scalar(b, j, x)
+    }
+
+    /// True when the two blocks have no item in common, by scalar code
+    fn disjoint_scalar<T: Copy+PartialEq, const N: usize>(a: &[T;N], b: &[T;N]) -> bool {
+        a.iter().all(|x| !b.contains(x))
+    }
+
+    /// True when the two blocks have no item in common, comparing a with the eight rotations of b
+    #[target_feature(enable = "avx2")]
+    fn disjoint_u32_avx2(a: &[u32;8], b: &[u32;8]) -> bool {
+        // SAFETY: a and b hold eight items, unaligned loads
+        let (av, bv) = unsafe { (_mm256_loadu_si256(a.as_ptr() as *const __m256i), _mm256_loadu_si256(b.as_ptr() as *const __m256i)) };
+        let bs = _mm256_permute2x128_si256::<0x01>(bv, bv); // halves swapped
+        let eq = |v| _mm256_or_si256(
+            _mm256_or_si256(_mm256_cmpeq_epi32(av, v), _mm256_cmpeq_epi32(av, _mm256_shuffle_epi32::<0x39>(v))),
+            _mm256_or_si256(_mm256_cmpeq_epi32(av, _mm256_shuffle_epi32::<0x4E>(v)), _mm256_cmpeq_epi32(av, _mm256_shuffle_epi32::<0x93>(v))));
+        let any = _mm256_or_si256(eq(bv), eq(bs));
+        _mm256_testz_si256(any, any) == 1
+    }
+
+    /// True when the two blocks have no item in common, comparing a with the four rotations of b
+    #[target_feature(enable = "avx2")]
+    fn disjoint_u64_avx2(a: &[u64;4], b: &[u64;4]) -> bool {
+        // SAFETY: a and b hold four items, unaligned loads
+        let (av, bv) = unsafe { (_mm256_loadu_si256(a.as_ptr() as *const __m256i), _mm256_loadu_si256(b.as_ptr() as *const __m256i)) };
+        let any = _mm256_or_si256(
+            _mm256_or_si256(_mm256_cmpeq_epi64(av, bv), _mm256_cmpeq_epi64(av, _mm256_permute4x64_epi64::<0x39>(bv))),
+            _mm256_or_si256(_mm256_cmpeq_epi64(av, _mm256_permute4x64_epi64::<0x4E>(bv)), _mm256_cmpeq_epi64(av, _mm256_permute4x64_epi64::<0x93>(bv))));
+        _mm256_testz_si256(any, any) == 1
+    }
+
+    /// True when the two blocks have no item in common, comparing a with the four rotations of b
+    #[target_feature(enable = "sse2")]
+    fn disjoint_u32_sse2(a: &[u32;4], b: &[u32;4]) -> bool {
+        // SAFETY: a and b hold four items, unaligned loads
+        let (av, bv) = unsafe { (_mm_loadu_si128(a.as_ptr() as *const __m128i), _mm_loadu_si128(b.as_ptr() as *const __m128i)) };
+        let any = _mm_or_si128(
+            _mm_or_si128(_mm_cmpeq_epi32(av, bv), _mm_cmpeq_epi32(av, _mm_shuffle_epi32::<0x39>(bv))),
+            _mm_or_si128(_mm_cmpeq_epi32(av, _mm_shuffle_epi32::<0x4E>(bv)), _mm_cmpeq_epi32(av, _mm_shuffle_epi32::<0x93>(bv))));
+        _mm_movemask_epi8(any) == 0
+    }
+
+    sorted_ops!(#[target_feature(enable = "avx2")] u32, skip_u32_avx2 => intersect_u32_avx2, union_u32_avx2, difference_u32_avx2, differencea_u32_avx2);
+    sorted_ops!(#[target_feature(enable = "avx2")] u64, skip_u64_avx2 => intersect_u64_avx2, union_u64_avx2, difference_u64_avx2, differencea_u64_avx2);
+    sorted_ops!(#[target_feature(enable = "sse2")] u32, skip_u32_sse2 => intersect_u32_sse2, union_u32_sse2, difference_u32_sse2, differencea_u32_sse2);
+    // sse2 has no 64 bit comparison, so u64 stays scalar without avx2
+    sorted_ops!(#[target_feature(enable = "sse2")] u64, skip_scalar => intersect_u64_sse2, union_u64_sse2, difference_u64_sse2, differencea_u64_sse2);
+    merge_intersect!(#[target_feature(enable = "avx2")] u32, 8, disjoint_u32_avx2 => merge_u32_avx2);
+    merge_intersect!(#[target_feature(enable = "avx2")] u64, 4, disjoint_u64_avx2 => merge_u64_avx2);
+    merge_intersect!(#[target_feature(enable = "sse2")] u32, 4, disjoint_u32_sse2 => merge_u32_sse2);
+    // likewise, u64 blocks are compared by scalar code without avx2
+    merge_intersect!(#[target_feature(enable = "sse2")] u64, 2, disjoint_scalar => merge_u64_sse2);
+}
+
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use core::arch::aarch64::*;
+    use super::skip_scalar;
+    use crate::prelude::*;
+
+    /// Skips the items of b less than x, four lanes at a time
+    #[target_feature(enable = "neon")]
+    fn skip_u32_neon(b: &[u32], mut j: usize, x: u32) -> usize {
+        let xv = vdupq_n_u32(x);
+        while j + 4 <= b.len() {
+            // SAFETY: j+4 <= b.len()
+            let bv = unsafe { vld1q_u32(b.as_ptr().add(j)) };
+            let n = vaddvq_u32(vshrq_n_u32::<31>(vcltq_u32(bv, xv))) as usize;
+            j += n;
+            if n < 4 { return j };
+        }
+        skip_scalar(b, j, x)
+    }
+
+    /// Skips the items of b less than x, two lanes at a time
+    #[target_feature(enable = "neon")]
+    fn skip_u64_neon(b: &[u64], mut j: usize, x: u64) -> usize {
+        let xv = vdupq_n_u64(x);
+        while j + 2 <= b.len() {
+            // SAFETY: j+2 <= b.len()
+            let bv = unsafe { vld1q_u64(b.as_ptr().add(j)) };
+            let n = vaddvq_u64(vshrq_n_u64::<63>(vcltq_u64(bv, xv))) as usize;
+            j += n;
+            if n < 2 { return j };
+        }
+        skip_scalar(b, j, x)
+    }
+
+    /// True when the two blocks have no item in common, comparing a with the four rotations of b
+    #[target_feature(enable = "neon")]
+    fn disjoint_u32_neon(a: &[u32;4], b: &[u32;4]) -> bool {
+        // SAFETY: a and b hold four items
+        let (av, bv) = unsafe { (vld1q_u32(a.as_ptr()), vld1q_u32(b.as_ptr())) };
+        let any = vorrq_u32(
+            vorrq_u32(vceqq_u32(av, bv), vceqq_u32(av, vextq_u32::<1>(bv, bv))),
+            vorrq_u32(vceqq_u32(av, vextq_u32::<2>(bv, bv)), vceqq_u32(av, vextq_u32::<3>(bv, bv))));
+        vmaxvq_u32(any) == 0
+    }
+
+    /// True when the two blocks have no item in common, comparing a with the two rotations of b
+    #[target_feature(enable = "neon")]
+    fn disjoint_u64_neon(a: &[u64;2], b: &[u64;2]) -> bool {
+        // SAFETY: a and b hold two items
+        let (av, bv) = unsafe { (vld1q_u64(a.as_ptr()), vld1q_u64(b.as_ptr())) };
+        let any = vorrq_u64(vceqq_u64(av, bv), vceqq_u64(av, vextq_u64::<1>(bv, bv)));
+        vmaxvq_u32(vreinterpretq_u32_u64(any)) == 0
+    }
+
+    sorted_ops!(#[target_feature(enable = "neon")] u32, skip_u32_neon => intersect_u32_neon, union_u32_neon, difference_u32_neon, differencea_u32_neon);
+    sorted_ops!(#[target_feature(enable = "neon")] u64, skip_u64_neon => intersect_u64_neon, union_u64_neon, difference_u64_neon, differencea_u64_neon);
+    merge_intersect!(#[target_feature(enable = "neon")] u32, 4, disjoint_u32_neon => merge_u32_neon);
+    merge_intersect!(#[target_feature(enable = "neon")] u64, 2, disjoint_u64_neon => merge_u64_neon);
+}
+
+/// Runs one operation on the kernel k, checked to be available
+macro_rules! dispatch {
+    ($k:ident, $a:ident, $b:ident, $scalar:ident, $avx2:ident, $sse2:ident, $neon:ident) => {{
+        assert!($k.is_available(), "simd: kernel {} not available", $k.name());
+        match $k {
+            Kernel::Scalar => $scalar($a, $b),
+            // SAFETY: avx2 was detected, sse2 is the x86_64 baseline
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Avx2 => unsafe { x86::$avx2($a, $b) },
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Sse2 => unsafe { x86::$sse2($a, $b) },
+            // SAFETY: neon was detected
+            #[cfg(target_arch = "aarch64")]
+            Kernel::Neon => unsafe { neon::$neon($a, $b) },
+            #[allow(unreachable_patterns)]
+            _ => unreachable!()
+        }
+    }}
+}
+
+/// The shorter and the longer of a and b, when the longer is at least `SKIP_RATIO` times longer
+fn skewed<'a,T>(a: &'a [T], b: &'a [T]) -> Option<(&'a [T], &'a [T])> {
+    let (short, long) = if a.len() <= b.len() { (a,b) } else { (b,a) };
+    if long.len() >= SKIP_RATIO*short.len() { Some((short, long)) } else { None }
+}
+
+/// Implements SortedOps for one type, from its kernels
+macro_rules! impl_sorted_ops {
+    ($t:ty, [$($inter:ident),*], [$($merge:ident),*], [$($union:ident),*], [$($diff:ident),*], [$($diffa:ident),*]) => {
+        impl SortedOps for $t {
+            fn sorted_intersect_with(k: Kernel, a: &[$t], b: &[$t]) -> Vec<$t> {
+                match skewed(a, b) { Some((s,l)) => dispatch!(k, s, l, $($inter),*), None => dispatch!(k, a, b, $($merge),*) }
+            }
+            fn sorted_union_with(k: Kernel, a: &[$t], b: &[$t]) -> Vec<$t> {
+                match skewed(a, b) { Some((s,l)) => dispatch!(k, s, l, $($union),*), None => a.merge(b) }
+            }
+            fn sorted_difference_with(k: Kernel, a: &[$t], b: &[$t]) -> Vec<$t> {
+                match skewed(a, b) {
+                    None => a.diff(b),
+                    Some(_) if a.len() <= b.len() => dispatch!(k, a, b, $($diff),*),
+                    Some(_) => dispatch!(k, a, b, $($diffa),*)
+                }
+            }
+        }
+    }
+}
+
+impl_sorted_ops!(u32, [intersect_u32, intersect_u32_avx2, intersect_u32_sse2, intersect_u32_neon],
+    [merge_u32, merge_u32_avx2, merge_u32_sse2, merge_u32_neon],
+    [union_u32, union_u32_avx2, union_u32_sse2, union_u32_neon],
+    [difference_u32, difference_u32_avx2, difference_u32_sse2, difference_u32_neon],
+    [differencea_u32, differencea_u32_avx2, differencea_u32_sse2, differencea_u32_neon]);
+impl_sorted_ops!(u64, [intersect_u64, intersect_u64_avx2, intersect_u64_sse2, intersect_u64_neon],
+    [merge_u64, merge_u64_avx2, merge_u64_sse2, merge_u64_neon],
+    [union_u64, union_u64_avx2, union_u64_sse2, union_u64_neon],
+    [difference_u64, difference_u64_avx2, difference_u64_sse2, difference_u64_neon],
+    [differencea_u64, differencea_u64_avx2, differencea_u64_sse2, differencea_u64_neon]);
+
+/// The operations of `SortedOps`, as run by the Set operations
+#[derive(Clone,Copy)]
+pub(crate) enum SetOp { Intersect, Union, Difference }
+
+/// Runs op by the best kernel when T is u32 or u64, otherwise returns None
+pub(crate) fn sorted_op<T: 'static>(op: SetOp, a: &[T], b: &[T]) -> Option<Vec<T>> {
+    run::<T,u32>(op, a, b).or_else(|| run::<T,u64>(op, a, b))
+}
+
+/// Runs op on a and b as slices of U, when T is U
+fn run<T: 'static, U: SortedOps+'static>(op: SetOp, a: &[T], b: &[T]) -> Option<Vec<T>> {
+    if TypeId::of::<T>() != TypeId::of::<U>() { return None };
+    // SAFETY: T and U are the same type
+    let (a, b) = unsafe { (core::slice::from_raw_parts(a.as_ptr() as *const U, a.len()), core::slice::from_raw_parts(b.as_ptr() as *const U, b.len())) };
+    let mut res = Some(match op {
+        SetOp::Intersect => U::sorted_intersect(a, b),
+        SetOp::Union => U::sorted_union(a, b),
+        SetOp::Difference => U::sorted_difference(a, b)
+    });
+    (&mut res as &mut dyn Any).downcast_mut::<Option<Vec<T>>>()?.take()
+}
@@ -0,0 +1,187 @@
+use crate::prelude::*;
+use crate::{SType,Set,Quantify,Permutation,trivindex,radix::radix_sortindex_keys};
+use crate::compat::{Indices,Vecops};
+use core::cmp::Ordering;
+
+/// Choice of the sorting algorithm, for the `_with` constructors and converters below,
+/// and (as it implements `Quantify`) for `mordered_by` and `mindexed_by`
+#[derive(Default)]
+pub enum SortStrategy<T> {
+    /// merge sort of indxvec (the default, used by `new` and the `to_` converters)
+    #[default]
+    Merge,
+    /// hashsort of indxvec, by the given quantification (as the closures passed to `mordered`)
+    Hash(fn(&T) -> f64),
+    /// std library stable sort
+    Stable,
+    /// std library unstable sort
+    Unstable,
+    /// LSD radix sort of the given exact u64 keys (see `radix::RadixKey`)
+    Radix(fn(&T) -> u64),
+    /// binary insertion sort, for tiny inputs
+    Insertion,
+    /// sort exploiting any already sorted runs, for nearly sorted inputs
+    Adaptive
+}
+
+impl<T> Clone for SortStrategy<T> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T> Copy for SortStrategy<T> {}
+
+/// Comparison that panics on incomparable items, as the indxvec sorts do
+fn cmp<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).expect("SortStrategy comparison failure")
+}
+
+/// True when d is sorted in asc (non-decreasing) or desc (non-increasing) order
+pub fn is_sorted<T: PartialOrd>(d: &[T], asc: bool) -> bool {
+    if asc { d.windows(2).all(|w| w[0] <= w[1]) } else { d.windows(2).all(|w| w[0] >= w[1]) }
+}
+
+/// Stable ascending sort index of d, exploiting its natural runs (as in Timsort):
+/// splits d into maximal non-decreasing or strictly decreasing runs,
+/// reverses the latter and then merges the runs pairwise.
+/// Already sorted d is recognised in one pass.
+pub fn adaptive_sortindex<T: PartialOrd>(d: &[T]) -> Vec<usize> {
+    let n = d.len();
+    let mut runs: Vec<Vec<usize>> = Vec::new();
+    let mut start = 0;
+    while start < n {
+        let mut end = start + 1;
+        if end < n && d[end] < d[start] { // strictly decreasing run
+            while end < n && d[end] < d[end-1] { end += 1 };
+            runs.push((start..end).rev().collect());
+        } else {
+            while end < n && d[end] >= d[end-1] { end += 1 };
+            runs.push((start..end).collect());
+        }
+        start = end;
+    }
+    while runs.len() > 1 {
+        let mut merged = Vec::with_capacity(runs.len().div_ceil(2));
+        let mut pairs = runs.into_iter();
+        while let Some(left) = pairs.next() {
+            match pairs.next() {
+                Some(right) => merged.push(merge_runs(d, &left, &right)),
+                None => merged.push(left)
+            }
+        }
+        runs = merged;
+    }
+    runs.pop().unwrap_or_default()
+}
+
+/// Stable merge of two ascending index runs into d
+fn merge_runs<T: PartialOrd>(d: &[T], left: &[usize], right: &[usize]) -> Vec<usize> {
+    let mut res = Vec::with_capacity(left.len() + right.len());
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        if d[right[j]] < d[left[i]] { res.push(right[j]); j += 1 } else { res.push(left[i]); i += 1 }
+    }
+    res.extend_from_slice(&left[i..]);
+    res.extend_from_slice(&right[j..]);
+    res
+}
+
+impl<T> Quantify<T> for SortStrategy<T> where T: PartialOrd+Clone {
+    fn sortindex(&self, d: &[T]) -> Vec<usize> {
+        match self {
+            SortStrategy::Merge => d.mergesort_indexed(),
+            SortStrategy::Hash(quantify) => d.hashsort_indexed(*quantify),
+            SortStrategy::Adaptive => adaptive_sortindex(d),
+            SortStrategy::Stable => {
+                let mut idx = trivindex(true, d.len());
+                idx.sort_by(|&a,&b| cmp(&d[a],&d[b]));
+                idx },
+            SortStrategy::Unstable => {
+                let mut idx = trivindex(true, d.len());
+                idx.sort_unstable_by(|&a,&b| cmp(&d[a],&d[b]));
+                idx },
+            SortStrategy::Radix(key) => radix_sortindex_keys(&d.iter().map(key).collect::<Vec<u64>>()),
+            SortStrategy::Insertion => {
+                let mut idx: Vec<usize> = Vec::with_capacity(d.len());
+                for i in 0..d.len() {
+                    let pos = idx.partition_point(|&j| cmp(&d[j],&d[i]) != Ordering::Greater);
+                    idx.insert(pos, i);
+                }
+                idx }
+        }
+    }
+}
+
+/// Constructors and converters sorting by a chosen algorithm
+impl<T> Set<T> where T: Copy+PartialOrd+Default {
+
+    /// All in one Initialiser, as `new`, sorting by quantify (e.g. a `SortStrategy`)
+    pub fn new_with(set_type: SType, d: &[T], asc: bool, quantify: impl Quantify<T>) -> Self {
+        match set_type {
+            SType::Ordered => Set::new_unordered(d).to_ordered_with(asc, quantify),
+            SType::Indexed => Set::new_unordered(d).to_indexed_with(asc, quantify),
+            SType::Ranked => Set::new_unordered(d).to_ranked_with(asc, quantify),
+            _ => Set::new(set_type, d, asc)
+        }
+    }
+
+    /// Ascending or descending sort index of unordered data, by quantify
+    fn index_with(&self, asc: bool, quantify: impl Quantify<T>) -> Vec<usize> {
+        if asc { quantify.sortindex(&self.data) } else { quantify.sortindex(&self.data).revs() }
+    }
+
+    /// Converts any Set type to ordered, as `to_ordered`,
+    /// sorting Unordered sets by quantify (e.g. a `SortStrategy`)
+    pub fn to_ordered_with(&self, asc: bool, quantify: impl Quantify<T>) -> Self {
+        match self.stype {
+            SType::Unordered => Set{ stype:SType::Ordered, ascending:asc,
+                data:self.index_with(asc, quantify).unindex(&self.data, true), index:Permutation(Vec::new()) },
+            _ => self.to_ordered(asc)
+        }
+    }
+
+    /// Converts any Set type to indexed, as `to_indexed`,
+    /// sorting Unordered sets by quantify (e.g. a `SortStrategy`)
+    pub fn to_indexed_with(&self, asc: bool, quantify: impl Quantify<T>) -> Self {
+        match self.stype {
+            SType::Unordered => Set{ stype:SType::Indexed, ascending:asc, data:self.data.clone(),
+                index:Permutation(self.index_with(asc, quantify)) },
+            _ => self.to_indexed(asc)
+        }
+    }
+
+    /// Converts any Set type to ranked, as `to_ranked`,
+    /// sorting Unordered sets by quantify (e.g. a `SortStrategy`)
+    pub fn to_ranked_with(&self, asc: bool, quantify: impl Quantify<T>) -> Self {
+        match self.stype {
+            SType::Unordered => Set{ stype:SType::Ranked, ascending:asc, data:self.data.clone(),
+                index:Permutation(self.index_with(asc, quantify).invindex()) },
+            _ => self.to_ranked(asc)
+        }
+    }
+
+    /// All in one Initialiser for nearly sorted data. Returns at once, with a trivial index,
+    /// when d is already strictly sorted in either direction (or sorted, for Ordered sets).
+    /// Sorted d with ties is indexed by `Set::new`, so that the tied items have the same index order.
+    /// Otherwise merges the natural runs of d, keeping tied items in data order.
+    pub fn new_adaptive(set_type: SType, d: &[T], asc: bool) -> Self {
+        if d.is_empty() { return Set::EMPTYSET };
+        let up = is_sorted(d, true);
+        if !up && !is_sorted(d, false) { return Set::new_with(set_type, d, asc, SortStrategy::Adaptive) };
+        match set_type {
+            SType::Ordered => Set{ stype:SType::Ordered, ascending:asc,
+                data: if up == asc { d.to_vec() } else { d.revs() }, index:Permutation(Vec::new()) },
+            SType::Indexed | SType::Ranked if d.windows(2).all(|w| w[0] != w[1]) =>
+                Set{ stype:set_type, ascending:asc, data:d.to_vec(), index:Permutation(trivindex(up == asc, d.len())) },
+            _ => Set::new(set_type, d, asc)
+        }
+    }
+
+    /// True when the data of self are sorted in asc order.
+    /// Answered without scanning for Ordered sets of the same order.
+    pub fn is_sorted(&self, asc: bool) -> bool {
+        match self.stype {
+            SType::Ordered if self.ascending == asc => true,
+            _ => is_sorted(&self.data, asc)
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::prelude::*;
+use crate::{SType,Set,MutSetOps,Permutation,trivindex,mutimpls::insertpos};
+
+/// Multi-column table: a Set of keys, plus rows of companion values (e.g. tuples),
+/// one row per key, kept parallel to `keys.data` through all the mutations below
+#[derive(Default,Clone)]
+pub struct Table<T,R> {
+    /// the key column
+    pub keys: Set<T>,
+    /// companion rows, rows\[i\] belongs to keys.data\[i\]
+    pub rows: Vec<R>
+}
+
+impl<T,R> Table<T,R> where T: Copy+PartialOrd+Default+'static {
+
+    /// New table of set_type, ordered by keys in asc order.
+    /// Rows must be as many as keys.
+    pub fn new(set_type: SType, keys: &[T], mut rows: Vec<R>, asc: bool) -> Self {
+        assert_eq!(keys.len(), rows.len(), "Table::new: keys and rows of different lengths");
+        let keys = match set_type {
+            SType::Ordered => { // one sort permutation, applied to both keys and rows
+                let indexed = Set::new_indexed(keys, asc);
+                indexed.mcosort(&mut rows);
+                indexed.to_ordered(asc) },
+            _ => Set::new(set_type, keys, asc)
+        };
+        if let SType::Empty = keys.stype { rows.clear() };
+        Table{ keys, rows }
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize { self.rows.len() }
+
+    /// True when the table has no rows
+    pub fn is_empty(&self) -> bool { self.rows.is_empty() }
+
+    /// Row of the first matching key, or None
+    pub fn get(&self, key: T) -> Option<&R> {
+        self.keys.search(key).map(|i| &self.rows[i])
+    }
+
+    /// Rows in the sort order of the keys (ascending for Unordered)
+    pub fn sorted_rows(&self) -> Vec<&R> {
+        let refs: Vec<&R> = self.rows.iter().collect();
+        self.keys.cosort(&refs)
+    }
+
+    /// Inserts key with its row
+    pub fn insert(&mut self, key: T, row: R) {
+        match self.keys.stype {
+            SType::Empty | SType::Ordered => {
+                let d = &self.keys.data;
+                let pos = insertpos(self.keys.ascending, d.len(), |i| d[i] < key, |i| d[i] > key);
+                self.keys.minsert(key);
+                self.rows.insert(pos, row); },
+            _ => { self.keys.minsert(key); self.rows.push(row); } // pushed to the end of data
+        }
+    }
+
+    /// Deletes the first matching key, returning its row, or None when not found
+    pub fn delete(&mut self, key: T) -> Option<R> {
+        let i = self.keys.search(key)?;
+        let unordered = matches!(self.keys.stype, SType::Unordered);
+        self.keys.mdelete(key);
+        if self.keys.data.is_empty() { self.keys = Set::EMPTYSET };
+        Some(if unordered { self.rows.swap_remove(i) } else { self.rows.remove(i) })
+    }
+
+    /// Reverses the order of the table
+    pub fn reverse(&mut self) {
+        if let SType::Unordered | SType::Ordered = self.keys.stype { self.rows.reverse() };
+        self.keys.mreverse();
+    }
+
+    /// Rearranges data and rows by permutation p
+    fn permute(&mut self, p: &Permutation) {
+        p.apply_mut(&mut self.keys.data);
+        p.apply_mut(&mut self.rows);
+    }
+
+    /// Converts the keys to Unordered, rows unchanged
+    pub fn unordered(&mut self) { self.keys.munordered() }
+
+    /// Converts the keys to Ordered, with the rows following
+    pub fn ordered(&mut self, asc: bool) {
+        if let SType::Empty = self.keys.stype { return };
+        let p = self.keys.order(asc);
+        self.permute(&p);
+        self.keys.index = Permutation(Vec::new());
+        self.keys.stype = SType::Ordered;
+        self.keys.ascending = asc;
+    }
+
+    /// Converts the keys to Indexed, rows unchanged
+    pub fn indexed(&mut self, asc: bool) {
+        self.keys = self.keys.to_indexed(asc);
+    }
+
+    /// Converts the keys to Ranked, rows unchanged
+    pub fn ranked(&mut self, asc: bool) {
+        self.keys = self.keys.to_ranked(asc);
+    }
+
+    /// Deletes the repeated keys, keeping the row of the first in the sort order.
+    /// As for sets, this leaves the keys sorted.
+    pub fn nonrepeat(&mut self) {
+        let stype = self.keys.stype;
+        let asc = match stype { SType::Empty => return, SType::Ordered => self.keys.ascending, _ => true };
+        let p = self.keys.order(asc);
+        self.permute(&p);
+        let mut keep = 0_usize;
+        for i in 0..self.keys.data.len() {
+            if i == 0 || self.keys.data[i] != self.keys.data[keep-1] {
+                self.keys.data.swap(keep, i);
+                self.rows.swap(keep, i);
+                keep += 1;
+            }
+        }
+        self.keys.data.truncate(keep);
+        self.rows.truncate(keep);
+        if let SType::Indexed | SType::Ranked = stype {
+            self.keys.index = Permutation(trivindex(self.keys.ascending, keep))
+        };
+    }
+}
@@ -0,0 +1,72 @@
+use crate::prelude::*;
+use crate::compat::Vecops;
+
+/// Replaces the items of two lists by ids, equal items getting the same id.
+/// Returns the two lists of ids and the number of distinct ids.
+fn ids<T: PartialOrd+Copy>(l1: &[T], l2: &[T]) -> (Vec<usize>, Vec<usize>, usize) {
+    let mut pooled = l1.to_vec();
+    pooled.extend_from_slice(l2);
+    let mut id = vec![0_usize; pooled.len()];
+    let sortindex = pooled.mergesort_indexed();
+    let mut next = 0_usize;
+    for (i,&sub) in sortindex.iter().enumerate() {
+        if i > 0 && pooled[sortindex[i-1]] != pooled[sub] { next += 1 };
+        id[sub] = next;
+    }
+    let id2 = id.split_off(l1.len());
+    (id, id2, if pooled.is_empty() { 0 } else { next + 1 })
+}
+
+/// Sizes of the overlaps X_d of the top d items of l1 and l2, for d = 1..=depth.
+/// Beyond the end of a list, all of its items remain counted.
+/// Items repeated within a list are counted once.
+fn overlapsizes<T: PartialOrd+Copy>(l1: &[T], l2: &[T], depth: usize) -> Vec<usize> {
+    let (id1, id2, n) = ids(l1, l2);
+    let (mut seen1, mut seen2) = (vec![false; n], vec![false; n]);
+    let mut x = 0_usize;
+    (0..depth).map(|d| {
+        if let Some(&i) = id1.get(d) { if !seen1[i] { seen1[i] = true; if seen2[i] { x += 1 } } };
+        if let Some(&i) = id2.get(d) { if !seen2[i] { seen2[i] = true; if seen1[i] { x += 1 } } };
+        x
+    }).collect()
+}
+
+/// Overlap@d curve: the proportions of the top d items of l1 and l2 that are common,
+/// for d = 1..=k
+pub fn overlaps<T: PartialOrd+Copy>(l1: &[T], l2: &[T], k: usize) -> Vec<f64> {
+    overlapsizes(l1, l2, k).iter().enumerate().map(|(d,&x)| x as f64/(d+1) as f64).collect()
+}
+
+/// Average overlap of l1 and l2 down to depth k: the mean of the overlap@d curve
+pub fn average_overlap<T: PartialOrd+Copy>(l1: &[T], l2: &[T], k: usize) -> f64 {
+    if k == 0 { return 0_f64 };
+    overlaps(l1, l2, k).iter().sum::<f64>() / k as f64
+}
+
+/// Rank-biased overlap of l1 and l2 with persistence p in (0,1),
+/// truncated to the evaluated depth (the longer length): the lower bound of the full RBO
+pub fn rbo<T: PartialOrd+Copy>(l1: &[T], l2: &[T], p: f64) -> f64 {
+    assert!(p > 0.0 && p < 1.0, "rbo: persistence p must be in (0,1)");
+    let depth = l1.len().max(l2.len());
+    let mut weight = 1.0 - p;
+    overlaps(l1, l2, depth).iter().map(|a| { let w = weight*a; weight *= p; w }).sum()
+}
+
+/// Extrapolated rank-biased overlap of l1 and l2 with persistence p in (0,1),
+/// assuming that their agreement continues beyond the evaluated depth.
+/// Handles lists of uneven lengths (Webber et al. 2010, equation 32).
+pub fn rbo_ext<T: PartialOrd+Copy>(l1: &[T], l2: &[T], p: f64) -> f64 {
+    assert!(p > 0.0 && p < 1.0, "rbo_ext: persistence p must be in (0,1)");
+    let (s, l) = (l1.len().min(l2.len()), l1.len().max(l2.len()));
+    if s == 0 { return 0_f64 };
+    let x = overlapsizes(l1, l2, l);
+    let (xs, xl) = (x[s-1] as f64, x[l-1] as f64);
+    let (mut sum, mut pd) = (0_f64, 1_f64);
+    for (i,&xd) in x.iter().enumerate() {
+        let d = (i+1) as f64;
+        pd *= p;
+        sum += xd as f64/d*pd;
+        if i >= s { sum += xs*(d-s as f64)/(s as f64*d)*pd };
+    }
+    (1.0-p)/p*sum + ((xl-xs)/l as f64 + xs/s as f64)*pd
+}
@@ -0,0 +1,177 @@
+use crate::prelude::*;
+use crate::{SType,Set,MutSetOps,Permutation,compat::MinMax};
+use core::ops::Range;
+
+/// Common methods of the typed sets: each wraps a Set of its own SType,
+/// (with no data yet, rather than Empty, when empty), so that conversions change the type
+macro_rules! typed_set {
+    ($name:ident, $stype:expr) => {
+        impl<T> $name<T> where T: Copy+PartialOrd+Default {
+
+            /// Wraps s, which must be of this SType (or Empty)
+            fn wrap(s: Set<T>) -> Self {
+                if s.data.is_empty() { return $name(Set{ stype:$stype, ascending:s.ascending,
+                    data:s.data, index:Permutation(Vec::new()) }) };
+                $name(s)
+            }
+
+            /// Borrows the dynamic Set
+            pub fn as_set(&self) -> &Set<T> { &self.0 }
+
+            /// Converts into the dynamic Set (`EMPTYSET` when empty)
+            pub fn into_set(self) -> Set<T> { self.into() }
+
+            /// The data, in the storage order of this type
+            pub fn data(&self) -> &[T] { &self.0.data }
+
+            /// Number of items
+            pub fn len(&self) -> usize { self.0.data.len() }
+
+            /// True when there are no items
+            pub fn is_empty(&self) -> bool { self.0.data.is_empty() }
+
+            /// Finds minimum, minimum's first index, maximum, maximum's first index
+            pub fn infsup(&self) -> MinMax<T> { self.0.infsup() }
+
+            /// Subscript of the first m in data, or None
+            pub fn search(&self, m: T) -> Option<usize> { self.0.search(m) }
+
+            /// True if m is a member
+            pub fn member(&self, m: T) -> bool { self.0.member(m) }
+
+            /// Inserts item, keeping the type
+            pub fn minsert(&mut self, item: T) where T: 'static { self.0.minsert(item) }
+
+            /// Deletes the first matching item, returns false when not found
+            pub fn mdelete(&mut self, item: T) -> bool where T: 'static { self.0.mdelete(item) }
+
+            /// Union, of the same type as self
+            pub fn union(&self, s: &Set<T>) -> Self where T: 'static { Self::wrap(self.0.union(s)) }
+
+            /// Intersection, of the same type as self
+            pub fn intersection(&self, s: &Set<T>) -> Self where T: 'static { Self::wrap(self.0.intersection(s)) }
+
+            /// Complement of s in self, of the same type as self
+            pub fn difference(&self, s: &Set<T>) -> Self where T: 'static { Self::wrap(self.0.difference(s)) }
+
+            /// Converts to UnorderedSet, discarding the order
+            pub fn to_unordered(&self) -> UnorderedSet<T> { UnorderedSet::wrap(self.0.to_unordered()) }
+
+            /// Converts to OrderedSet in asc order
+            pub fn to_ordered(&self, asc: bool) -> OrderedSet<T> { OrderedSet::wrap(self.0.to_ordered(asc)) }
+
+            /// Converts to IndexedSet in asc order
+            pub fn to_indexed(&self, asc: bool) -> IndexedSet<T> { IndexedSet::wrap(self.0.to_indexed(asc)) }
+
+            /// Converts to RankedSet in asc order
+            pub fn to_ranked(&self, asc: bool) -> RankedSet<T> { RankedSet::wrap(self.0.to_ranked(asc)) }
+        }
+
+        /// Accepts only a Set of this SType, or Empty, returning any other Set back as the error
+        impl<T> TryFrom<Set<T>> for $name<T> where T: Copy+PartialOrd+Default {
+            type Error = Set<T>;
+            fn try_from(s: Set<T>) -> Result<Self,Set<T>> {
+                match (s.stype, $stype) {
+                    (SType::Empty,_) | (SType::Unordered,SType::Unordered) | (SType::Ordered,SType::Ordered)
+                    | (SType::Indexed,SType::Indexed) | (SType::Ranked,SType::Ranked) => Ok(Self::wrap(s)),
+                    _ => Err(s)
+                }
+            }
+        }
+
+        /// Unwraps the Set, `EMPTYSET` when empty
+        impl<T> From<$name<T>> for Set<T> where T: Copy+PartialOrd+Default {
+            fn from(s: $name<T>) -> Self { if s.0.data.is_empty() { Set::EMPTYSET } else { s.0 } }
+        }
+
+        impl<T: core::fmt::Display+Copy> core::fmt::Display for $name<T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result { self.0.fmt(f) }
+        }
+    }
+}
+
+/// Set statically known to be Unordered
+#[derive(Clone)]
+pub struct UnorderedSet<T>(Set<T>);
+
+/// Set statically known to be Ordered
+#[derive(Clone)]
+pub struct OrderedSet<T>(Set<T>);
+
+/// Set statically known to be Indexed
+#[derive(Clone)]
+pub struct IndexedSet<T>(Set<T>);
+
+/// Set statically known to be Ranked
+#[derive(Clone)]
+pub struct RankedSet<T>(Set<T>);
+
+typed_set!(UnorderedSet, SType::Unordered);
+typed_set!(OrderedSet, SType::Ordered);
+typed_set!(IndexedSet, SType::Indexed);
+typed_set!(RankedSet, SType::Ranked);
+
+impl<T> UnorderedSet<T> where T: Copy+PartialOrd+Default {
+    /// Initialiser, from data
+    pub fn new(d: &[T]) -> Self { Self::wrap(Set::new_unordered(d)) }
+}
+
+impl<T> OrderedSet<T> where T: Copy+PartialOrd+Default {
+
+    /// Initialiser, sorting data in asc order
+    pub fn new(d: &[T], asc: bool) -> Self { Self::wrap(Set::new_ordered(d, asc)) }
+
+    /// Order of the data: ascending (true), descending (false)
+    pub fn ascending(&self) -> bool { self.0.ascending }
+
+    /// The k-th item in the order of the set, O(1)
+    pub fn select(&self, k: usize) -> Option<T> { self.0.data.get(k).copied() }
+
+    /// Slice of the items within r (r.start inclusive, r.end exclusive), in the order of the set
+    pub fn range(&self, r: Range<T>) -> &[T] {
+        let d = &self.0.data;
+        let (lo, hi) = if self.0.ascending {
+            (d.partition_point(|x| *x < r.start), d.partition_point(|x| *x < r.end)) }
+            else { (d.partition_point(|x| *x >= r.end), d.partition_point(|x| *x >= r.start)) };
+        &d[lo..hi.max(lo)]
+    }
+}
+
+impl<T> IndexedSet<T> where T: Copy+PartialOrd+Default {
+
+    /// Initialiser, indexing data in asc order
+    pub fn new(d: &[T], asc: bool) -> Self { Self::wrap(Set::new_indexed(d, asc)) }
+
+    /// Order of the index: ascending (true), descending (false)
+    pub fn ascending(&self) -> bool { self.0.ascending }
+
+    /// The sort index
+    pub fn sortindex(&self) -> &Permutation { &self.0.index }
+
+    /// The k-th item in the order of the set, O(1)
+    pub fn select(&self, k: usize) -> Option<T> { self.0.index.get(k).map(|&i| self.0.data[i]) }
+
+    /// Items within r (r.start inclusive, r.end exclusive), in the order of the set
+    pub fn range(&self, r: Range<T>) -> impl Iterator<Item = T> + '_ {
+        let (d, idx) = (&self.0.data, &self.0.index);
+        let (lo, hi) = if self.0.ascending {
+            (idx.partition_point(|&i| d[i] < r.start), idx.partition_point(|&i| d[i] < r.end)) }
+            else { (idx.partition_point(|&i| d[i] >= r.end), idx.partition_point(|&i| d[i] >= r.start)) };
+        idx[lo..hi.max(lo)].iter().map(move |&i| d[i])
+    }
+}
+
+impl<T> RankedSet<T> where T: Copy+PartialOrd+Default {
+
+    /// Initialiser, ranking data in asc order
+    pub fn new(d: &[T], asc: bool) -> Self { Self::wrap(Set::new_ranked(d, asc)) }
+
+    /// Order of the ranks: ascending (true), descending (false)
+    pub fn ascending(&self) -> bool { self.0.ascending }
+
+    /// The ranks, parallel to data
+    pub fn ranks(&self) -> &Permutation { &self.0.index }
+
+    /// Rank of the i-th data item, O(1)
+    pub fn rank(&self, i: usize) -> Option<usize> { self.0.index.get(i).copied() }
+}
@@ -0,0 +1,152 @@
+use crate::prelude::*;
+use crate::{SType,Set,Permutation,ascdesc,strategy::is_sorted,gallop::{self,AscView}};
+use crate::compat::{MinMax,Indices,Printing,Vecops};
+use alloc::borrow::Cow;
+
+/// Read-only Set over borrowed data, owning at most its index.
+/// Built over a slice, or borrowed whole from a Set by `Set::view`.
+#[derive(Clone)]
+pub struct SetView<'a,T> {
+    /// type of the set
+    pub stype: SType,
+    /// order: ascending (true), descending (false)
+    pub ascending: bool,
+    /// borrowed data
+    pub data: &'a [T],
+    /// index: sort index of Indexed sets, ranks of Ranked sets, empty otherwise
+    pub index: Cow<'a,[usize]>
+}
+
+impl<T: core::fmt::Display> core::fmt::Display for SetView<'_,T> where T:Copy {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match &self.stype {
+            SType::Empty =>  writeln!(f,"Empty SetView"),
+            SType::Unordered => writeln!(f, "Unordered SetView\nData: {}",self.data.gr()),
+            SType::Ordered => writeln!(f, "Ordered {} SetView\nData: {}",ascdesc(self.ascending),self.data.gr()),
+            SType::Indexed => writeln!(f, "Indexed {} SetView\nData: {}\nIndex: {}",ascdesc(self.ascending),self.data.gr(),self.index.yl()),
+            SType::Ranked => writeln!(f, "Ranked {} SetView\nData: {}\nRanks: {}",ascdesc(self.ascending),self.data.gr(),self.index.yl())
+        }
+    }
+}
+
+impl<'a,T> SetView<'a,T> where T: Copy+PartialOrd+Default {
+
+    /// All in one Initialiser, as `Set::new`, but borrowing d.
+    /// Only the index is computed and owned, so an Ordered view requires d already sorted in asc order.
+    pub fn new(set_type: SType, d: &'a [T], asc: bool) -> Self {
+        if d.is_empty() { return SetView{ stype:SType::Empty, ascending:true, data:d, index:Cow::Borrowed(&[]) } };
+        match set_type {
+            SType::Empty => SetView{ stype:SType::Empty, ascending:true, data:&d[..0], index:Cow::Borrowed(&[]) },
+            SType::Unordered => SetView{ stype:SType::Unordered, ascending:true, data:d, index:Cow::Borrowed(&[]) },
+            SType::Ordered => {
+                assert!(is_sorted(d, asc), "SetView::new: data not sorted for an Ordered view");
+                SetView{ stype:SType::Ordered, ascending:asc, data:d, index:Cow::Borrowed(&[]) } },
+            SType::Indexed => SetView{ stype:SType::Indexed, ascending:asc, data:d,
+                index: Cow::Owned(if asc { d.mergesort_indexed() } else { d.mergesort_indexed().revs() }) },
+            SType::Ranked => SetView{ stype:SType::Ranked, ascending:asc, data:d,
+                index: Cow::Owned(if asc { d.mergesort_indexed().invindex() } else { d.mergesort_indexed().revs().invindex() }) }
+        }
+    }
+
+    /// Initialiser - Unordered view of d
+    pub fn new_unordered(d: &'a [T]) -> Self { Self::new(SType::Unordered, d, true) }
+
+    /// Initialiser - Ordered view of d, which must be sorted in asc order
+    pub fn new_ordered(d: &'a [T], asc: bool) -> Self { Self::new(SType::Ordered, d, asc) }
+
+    /// Const Initialiser - Ordered view of d, which the caller guarantees to be sorted in asc order.
+    /// Unchecked, so usable for static lookup tables: `static KEYWORDS: SetView<&str> = SetView::new_sorted(&[...], true);`
+    pub const fn new_sorted(d: &'a [T], asc: bool) -> Self {
+        let stype = if d.is_empty() { SType::Empty } else { SType::Ordered };
+        SetView{ stype, ascending: asc || d.is_empty(), data:d, index:Cow::Borrowed(&[]) }
+    }
+
+    /// Initialiser - Indexed view of d, owning only the sort index
+    pub fn new_indexed(d: &'a [T], asc: bool) -> Self { Self::new(SType::Indexed, d, asc) }
+
+    /// Initialiser - Ranked view of d, owning only the ranks
+    pub fn new_ranked(d: &'a [T], asc: bool) -> Self { Self::new(SType::Ranked, d, asc) }
+
+    /// Number of items
+    pub fn len(&self) -> usize { self.data.len() }
+
+    /// True when the view has no items
+    pub fn is_empty(&self) -> bool { self.data.is_empty() }
+
+    /// Owned Set copy of the view
+    pub fn to_set(&self) -> Set<T> {
+        if self.data.is_empty() { return Set::EMPTYSET };
+        Set{ stype:self.stype, ascending:self.ascending, data:self.data.to_vec(), index:Permutation(self.index.to_vec()) }
+    }
+
+    /// Items in the order of the view (data order for Unordered)
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        let order: Cow<[usize]> = match self.stype {
+            SType::Ranked => Cow::Owned(self.index.invindex()),
+            SType::Indexed => Cow::Borrowed(&self.index),
+            _ => Cow::Owned(Vec::new())
+        };
+        let n = self.data.len();
+        (0..n).map(move |i| if order.is_empty() { self.data[i] } else { self.data[order[i]] })
+    }
+
+    /// Finds minimum, minimum's first index, maximum, maximum's first index
+    pub fn infsup(&self) -> MinMax<T> {
+        let last = self.data.len().saturating_sub(1);
+        let (first, lastpos) = match self.stype {
+            SType::Empty => return Default::default(),
+            SType::Unordered => return self.data.minmax(),
+            SType::Ordered => (0, last),
+            SType::Indexed => (self.index[0], self.index[last]),
+            SType::Ranked => { let si = self.index.invindex(); (si[0], si[last]) }
+        };
+        let (minindex, maxindex) = if self.ascending { (first, lastpos) } else { (lastpos, first) };
+        MinMax{ min:self.data[minindex], minindex, max:self.data[maxindex], maxindex }
+    }
+
+    /// Search the view for m.
+    /// Returns the subscript of the first m or None
+    pub fn search(&self, m: T) -> Option<usize> {
+        match self.stype {
+            SType::Empty => None,
+            SType::Unordered => self.data.member(m,true),
+            SType::Ordered => { let r = self.data.binsearch(&m);
+                if r.start == r.end { None } else { Some(r.start) } },
+            SType::Indexed => { let r = self.data.binsearch_indexed(&self.index,&m);
+                if r.start == r.end { None } else { Some(self.index[r.start]) } },
+            SType::Ranked => { let sortindex = self.index.invindex();
+                let r = self.data.binsearch_indexed(&sortindex,&m);
+                if r.start == r.end { None } else { Some(sortindex[r.start]) } }
+        }
+    }
+
+    /// True if m is a member of the view
+    pub fn member(&self, m: T) -> bool { self.search(m).is_some() }
+
+    /// Ascending view of the items, for the algebra below
+    fn asc(&self) -> AscView<'_,T> { AscView::of(self.stype, self.ascending, self.data, &self.index) }
+
+    /// Owned Ordered Set of sorted items, in the order of self (ascending for Unordered)
+    fn owned(&self, d: Vec<T>) -> Set<T> {
+        if d.is_empty() { return Set::EMPTYSET };
+        let asc = self.ascending || matches!(self.stype, SType::Unordered);
+        Set{ stype:SType::Ordered, ascending:asc, data: if asc { d } else { d.revs() }, index:Permutation(Vec::new()) }
+    }
+
+    /// Union of two views, as an owned Ordered Set
+    pub fn union(&self, s: &SetView<T>) -> Set<T> { self.owned(gallop::union_views(&self.asc(), &s.asc())) }
+
+    /// Intersection of two views, as an owned Ordered Set
+    pub fn intersection(&self, s: &SetView<T>) -> Set<T> { self.owned(gallop::intersect_views(&self.asc(), &s.asc())) }
+
+    /// Complement of s in self, as an owned Ordered Set
+    pub fn difference(&self, s: &SetView<T>) -> Set<T> { self.owned(gallop::difference_views(&self.asc(), &s.asc())) }
+}
+
+impl<T> Set<T> where T: Copy+PartialOrd+Default {
+
+    /// Read-only view of self, borrowing both its data and its index
+    pub fn view(&self) -> SetView<'_,T> {
+        SetView{ stype:self.kind(), ascending:self.ascending || self.data.is_empty(), data:&self.data, index:Cow::Borrowed(&self.index) }
+    }
+}
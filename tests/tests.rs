@@ -1,9 +1,8 @@
 #![allow(unused_imports)]
 #![allow(dead_code)]
 #[cfg(test)]
-
 // use anyhow::{Result};
-use sets::{Set,MutSetOps};
+use sets::{Set,MutSetOps,trivindex};
 use indxvec::{Printing,Indices,Vecops};
 
 #[test]
@@ -103,4 +102,731 @@ fn nlptest() {
    println!("Difference-> {}",&diff);
    diff.mnonrepeat();
    println!("Nonrepeat -> {}",diff);   
-}
\ No newline at end of file
+}
+#[test]
+fn minhashtest() {
+   use sets::minhash::{MinHash,LshIndex};
+   let doc = |s:&'static str| Set::new_unordered(&s.split(' ').collect::<Vec<_>>());
+   let s1 = doc("the quick brown fox jumps over the lazy dog");
+   let s2 = doc("the quick brown fox jumps over the lazy cat");
+   let s3 = doc("alphabetic ordering puts punctuation first");
+   let m1 = MinHash::new(&s1,128,42);
+   let m2 = MinHash::new(&s2,128,42);
+   assert_eq!(m1,MinHash::new(&s1,128,42)); // deterministic
+   println!("Exact Jaccard: {} estimated: {}",s1.jaccard(&s2).gr(),m1.jaccard(&m2).gr());
+   assert!((s1.jaccard(&s2)-7./9.).abs() < 1e-10);
+   assert!((m1.jaccard(&m2)-7./9.).abs() < 0.2);
+   let mut lsh = LshIndex::new(16,4,42);
+   lsh.insert(&s1);
+   lsh.insert(&s3);
+   let found = lsh.query_exact(&s2,0.5);
+   println!("Near duplicates of s2: {:?}",found);
+   assert_eq!(found.len(),1);
+   assert_eq!(found[0].0,0);
+}
+
+#[test]
+fn correlationtest() {
+   use sets::ranking::{spearman,kendall,kendall_distance,footrule};
+   let x = [1.,2.,3.,4.,5.];
+   let y = [5.,6.,7.,8.,7.];
+   println!("Spearman: {} Kendall: {}",spearman(&x,&y).gr(),kendall(&x,&y).gr());
+   assert!((spearman(&x,&y)-0.8207826816681233).abs() < 1e-12);
+   assert!((kendall(&x,&y)-0.7378647873726218).abs() < 1e-12);
+   let s1 = Set::new_ranked(&x,true);
+   let s2 = Set::new_ranked(&y,false);
+   assert!((s1.kendall(&s2)+kendall(&x,&y)).abs() < 1e-12);
+   for (a,b) in [(Set::new_unordered(&x),Set::new_indexed(&y,true)),(Set::new_indexed(&x,false),Set::new_ranked(&y,false))] {
+      assert!((a.spearman(&b)-spearman(&x,&y)).abs() < 1e-12);
+      assert!((a.kendall(&b)-kendall(&x,&y)).abs() < 1e-12);
+   }
+   assert!((Set::new_unordered(&x).spearman(&s2)+spearman(&x,&y)).abs() < 1e-12);
+   let ordered = std::panic::catch_unwind(|| Set::new_ordered(&x,true).spearman(&Set::new_ordered(&y,true)));
+   assert!(ordered.is_err());
+   assert_eq!(kendall_distance(&[0,1,2,3],&[3,2,1,0]),6);
+   for (r1,r2) in [([0,1,2,3],[3,2,1,1]),([0,1,2,4],[3,2,1,0])] {
+      assert!(std::panic::catch_unwind(|| kendall_distance(&r1,&r2)).is_err());
+   }
+   assert_eq!(footrule(&[0,1,2,3],&[3,2,1,0]),8);
+   let s3 = Set::new_ranked(&[4.,3.,2.,1.,0.],true);
+   assert_eq!(s1.kendall_distance(&s3),10);
+   assert_eq!(s1.kendall_distance(&s1.reverse()),10);
+}
+
+#[test]
+fn tiestest() {
+   use sets::ranking::RankMethod;
+   let s = Set::new_ranked(&[3.,10.,1.,10.,5.],true);
+   println!("{}Average ranks: {:?}",s,s.ranks(RankMethod::Average));
+   assert_eq!(s.ranks(RankMethod::Average),vec![1.,3.5,0.,3.5,2.]);
+   assert_eq!(s.ranks(RankMethod::Min),vec![1.,3.,0.,3.,2.]);
+   assert_eq!(s.ranks(RankMethod::Max),vec![1.,4.,0.,4.,2.]);
+   assert_eq!(s.ranks(RankMethod::Dense),vec![1.,3.,0.,3.,2.]);
+   assert_eq!(s.ranks(RankMethod::Ordinal),vec![1.,3.,0.,4.,2.]);
+   let d = s.reverse();
+   assert_eq!(d.ranks(RankMethod::Min),vec![3.,0.,4.,0.,2.]);
+   assert_eq!(d.ranks(RankMethod::Dense),vec![2.,0.,3.,0.,1.]);
+   assert_eq!(d.ranks(RankMethod::Ordinal),vec![3.,0.,4.,1.,2.]);
+}
+
+#[test]
+fn ranktest() {
+   use sets::ranktests::{mann_whitney,wilcoxon_signed,kruskal_wallis};
+   let s1 = Set::new_unordered(&[1.,2.,3.,4.,5.]);
+   let s2 = Set::new_unordered(&[6.,7.,8.,9.,10.]);
+   let mw = mann_whitney(&s1,&s2);
+   println!("Mann-Whitney {}",mw);
+   assert_eq!(mw.statistic,0.);
+   assert!((mw.p-0.01219).abs() < 1e-4);
+   let s3 = Set::new_unordered(&[0.,0.,0.,0.,10.]);
+   let w = wilcoxon_signed(&s1,&s3,|&x| x);
+   println!("Wilcoxon signed-rank {}",w);
+   assert_eq!(w.statistic,5.);
+   assert!((w.p-0.5002).abs() < 1e-3);
+   let kw = kruskal_wallis(&[&Set::new_unordered(&[1.,2.,3.]),
+      &Set::new_unordered(&[4.,5.,6.]),&Set::new_unordered(&[7.,8.,9.])]);
+   println!("Kruskal-Wallis {}",kw);
+   assert!((kw.statistic-7.2).abs() < 1e-10);
+   assert!((kw.p-(-3.6_f64).exp()).abs() < 5e-3);
+}
+
+#[test]
+fn aggregatetest() {
+   use sets::aggregate::{rankvecs,borda,mean_rank,median_rank,kemeny,kemeny_score,agreement,concordance};
+   let s1 = Set::new_ranked(&[1.,2.,3.,4.],true);
+   let s2 = Set::new_ranked(&[2.,1.,3.,4.],true);
+   let s3 = Set::new_ranked(&[1.,3.,2.,4.],true);
+   let ranks = rankvecs(&[&s1,&s2,&s3]);
+   println!("Borda {}",borda(&ranks));
+   assert_eq!(borda(&ranks).index,vec![0,1,2,3]);
+   assert_eq!(mean_rank(&ranks).index,vec![0,1,2,3]);
+   assert_eq!(median_rank(&ranks).data,vec![0.,1.,2.,3.]);
+   let k = kemeny(&ranks);
+   println!("Kemeny {}",k);
+   assert_eq!(k.index,vec![0,1,2,3]);
+   assert_eq!(kemeny_score(&ranks,&k.index),2);
+   let ag = agreement(&ranks);
+   assert_eq!((ag[1].min,ag[1].max),(0,2));
+   assert!((concordance(&ranks)-7./9.).abs() < 1e-10);
+   assert!((concordance(&[[0,1,2],[0,1,2]])-1.).abs() < 1e-10);
+}
+
+#[test]
+#[should_panic(expected = "aggregate: ranking 1 is not a permutation of 0..2")]
+fn aggregatevalidtest() {
+   sets::aggregate::borda(&[vec![1,0],vec![0,5]]);
+}
+
+#[test]
+fn topktest() {
+   use sets::topk::{overlaps,average_overlap,rbo,rbo_ext};
+   let l1 = ["a","b","c","d","e"];
+   let l2 = ["b","a","c","f"];
+   println!("Overlap@k: {:?}",overlaps(&l1,&l2,5));
+   assert_eq!(overlaps(&l1,&l2,5),vec![0.,1.,1.,0.75,0.6]);
+   assert!((average_overlap(&l1,&l2,3)-2./3.).abs() < 1e-12);
+   assert!((rbo(&l1,&l1,0.9)-(1.-0.9_f64.powi(5))).abs() < 1e-12);
+   assert!((rbo_ext(&l1,&l1,0.9)-1.).abs() < 1e-12);
+   assert!((rbo_ext(&l1,&["x","y"],0.9)).abs() < 1e-12);
+   let r = rbo_ext(&l1,&l2,0.9);
+   println!("RBO: {} RBO extrapolated: {}",rbo(&l1,&l2,0.9),r);
+   assert!(r > rbo(&l1,&l2,0.9) && r < 1.);
+   let s1 = Set::new_ordered(&[1,2,3,4],true);
+   let s2 = Set::new_indexed(&[4,3,2,1],true);
+   assert!((s1.rbo_ext(&s2,0.5)-1.).abs() < 1e-12);
+   assert!((s1.average_overlap(&s2.reverse(),4)-(0.+0.+2./3.+1.)/4.).abs() < 1e-12);
+}
+
+#[test]
+fn permutationtest() {
+   use sets::Permutation;
+   assert!(Permutation::new(vec![0,2,2]).is_none());
+   assert!(Permutation::new(vec![0,3,1]).is_none());
+   let p = Permutation::new(vec![2,0,1,4,3]).unwrap();
+   println!("Permutation: {} cycles: {:?}",p.yl(),p.cycles());
+   assert_eq!(p.compose(&p.inverse()),Permutation::identity(5));
+   assert_eq!(p.cycles(),vec![vec![0,2,1],vec![3,4]]);
+   assert_eq!(p.inversions(),3);
+   assert_eq!(p.sign(),-1);
+   assert_eq!(Permutation::reversed(4).inversions(),6);
+   let v = ['a','b','c','d','e'];
+   let mut w = v;
+   p.apply_mut(&mut w);
+   assert_eq!(p.apply(&v),w.to_vec());
+   assert_eq!(w,['c','a','b','e','d']);
+   let s = Set::new_indexed(&[3.,1.,2.],true);
+   assert_eq!(s.index.apply(&s.data),vec![1.,2.,3.]);
+   let mut r = Set::new_ranked(&[3.,1.,2.],true);
+   r.minsert(1.5);
+   assert!(Permutation::new(r.index.to_vec()).is_some());
+   assert_eq!(r.index,vec![3,0,2,1]);
+   assert_eq!(r.search(3.),Some(0));
+}
+
+#[test]
+fn cosorttest() {
+   use sets::{SType,table::Table};
+   let scores = [3.,1.,2.];
+   let ids = ["c","a","b"];
+   let s = Set::new_indexed(&scores,false);
+   assert_eq!(s.cosort(&ids),vec!["c","b","a"]);
+   let mut idcol = ids;
+   Set::new_ranked(&scores,true).mcosort(&mut idcol);
+   assert_eq!(idcol,["a","b","c"]);
+   let mut t = Table::new(SType::Ranked,&scores,vec![("c",30),("a",10),("b",20)],true);
+   t.insert(2.5,("bc",25));
+   assert_eq!(t.get(2.5),Some(&("bc",25)));
+   assert_eq!(t.sorted_rows(),vec![&("a",10),&("b",20),&("bc",25),&("c",30)]);
+   assert_eq!(t.delete(1.),Some(("a",10)));
+   t.ordered(false);
+   println!("{}",t.keys);
+   assert_eq!(t.rows,vec![("c",30),("bc",25),("b",20)]);
+   t.insert(2.,("b2",21));
+   t.reverse();
+   assert_eq!(t.keys.data,vec![2.,2.,2.5,3.]);
+   assert_eq!(t.rows.len(),4);
+   t.nonrepeat();
+   assert_eq!(t.keys.data,vec![2.,2.5,3.]);
+   assert_eq!(t.get(3.),Some(&("c",30)));
+   for asc in [true,false] {
+      let t = Table::new(SType::Ordered,&[3.,1.,2.,1.],vec![(3.,"c"),(1.,"a"),(2.,"b"),(1.,"a2")],asc);
+      assert!(t.keys.data.iter().zip(&t.rows).all(|(k,r)| *k == r.0));
+      assert_eq!(t.keys.data,if asc { vec![1.,1.,2.,3.] } else { vec![3.,2.,1.,1.] });
+   }
+   let t = Table::new(SType::Ordered,&[3.,1.,2.],vec!["c","a","b"],false);
+   assert_eq!(t.rows,vec!["c","b","a"]);
+}
+
+#[test]
+fn bykeytest() {
+   use sets::SType;
+   #[derive(Clone,Debug,PartialEq)]
+   struct Person { name: &'static str, age: u32 }
+   let people = [ Person{name:"Cecil",age:40}, Person{name:"Ann",age:25}, Person{name:"Bob",age:33} ];
+   let mut byage = Set::new_by_key(SType::Indexed,&people,true,|p:&Person| p.age);
+   assert_eq!(byage.get(&33).map(|p| p.name),Some("Bob"));
+   assert_eq!(byage.search_key(&34),None);
+   byage.minsert(Person{name:"Dora",age:30});
+   assert_eq!(byage.to_ordered(false).data.iter().map(|p| p.age).collect::<Vec<_>>(),vec![40,33,30,25]);
+   assert!(byage.mdelete(&Person{name:"anyone",age:25}));
+   assert_eq!(byage.len(),3);
+   let name = |p:&Person| p.name;
+   let mut byname = Set::new_by_key(SType::Ranked,&people,true,name);
+   assert_eq!(byname.index,vec![2,0,1]);
+   byname.mreverse();
+   assert_eq!(byname.search_key(&"Ann"),Some(1));
+   let others = Set::new_by_key(SType::Unordered,&[Person{name:"Ann",age:99}],true,name);
+   assert_eq!(byname.intersection(&others).data.len(),1);
+   assert_eq!(byname.difference(&others).len(),2);
+   assert_eq!(byname.union(&others).len(),4);
+}
+
+#[test]
+fn comparatortest() {
+   use sets::SType;
+   use std::cmp::Ordering;
+   let nocase = |a:&&str,b:&&str| a.to_lowercase().cmp(&b.to_lowercase());
+   let words = ["banana","Apple","cherry","apple","Banana"];
+   for stype in [SType::Unordered,SType::Ordered,SType::Indexed,SType::Ranked] {
+      let mut s = Set::new_by(stype,&words,true,nocase);
+      assert!(s.member(&"CHERRY"));
+      s.mnonrepeat();
+      assert_eq!(s.len(),3);
+      s.minsert("BANANA");
+      assert!(s.mdelete(&"banana") && s.mdelete(&"Banana"));
+      assert!(!s.member(&"banana"));
+      assert_eq!(s.to_ordered(true).data.len(),2);
+   }
+   let version = |a:&&str,b:&&str| -> Ordering {
+      let parse = |v:&str| v.split('.').map(|n| n.parse::<u32>().unwrap()).collect::<Vec<_>>();
+      parse(a).cmp(&parse(b)) };
+   let v1 = Set::new_by(SType::Ordered,&["1.10.0","1.2.0","1.9.3"],false,version);
+   assert_eq!(v1.data,vec!["1.10.0","1.9.3","1.2.0"]);
+   let v2 = v1.new_same(&["1.9.3","2.0"]);
+   assert_eq!(v1.union(&v2).data,vec!["2.0","1.10.0","1.9.3","1.9.3","1.2.0"]);
+   assert_eq!(v1.intersection(&v2).data,vec!["1.9.3"]);
+   assert_eq!(v1.difference(&v2).data,vec!["1.10.0","1.2.0"]);
+}
+
+#[test]
+fn sortspectest() {
+   use sets::{SType,setby::SortSpec};
+   #[derive(Clone,Debug,PartialEq)]
+   struct Employee { dept: &'static str, salary: u32, name: String }
+   let emp = |dept,salary,name: &str| Employee{dept,salary,name:name.to_string()};
+   let staff = [ emp("sales",50,"Eve"), emp("it",70,"Bob"), emp("sales",60,"Al"), emp("it",70,"Ann"), emp("hr",40,"Zed") ];
+   let spec = SortSpec::new().then(|e:&Employee| e.dept,true)
+      .then(|e:&Employee| e.salary,false).then_ref(|e:&Employee| e.name.as_str(),true);
+   assert_eq!(spec.len(),3);
+   let s = Set::new_by_spec(SType::Indexed,&staff,spec);
+   let names: Vec<_> = s.sorted(true).apply(&s.data).iter().map(|e| e.name.clone()).collect();
+   assert_eq!(names,vec!["Zed","Ann","Bob","Al","Eve"]);
+   assert_eq!(s.range_prefix(&("sales",)),Some(3..5));
+   assert_eq!(s.search_prefix(&("sales",)),vec![2,0]);
+   assert_eq!(s.search_prefix(&("it",70)),vec![3,1]);
+   assert_eq!(s.to_ranked(true).search_prefix(&("it",70)),vec![3,1]);
+   assert_eq!(s.search_prefix(&("it",70,"Bob")),vec![1]);
+   assert!(s.search_prefix(&("it",70,"Al")).is_empty());
+   assert_eq!(s.search_prefix(&()).len(),5);
+   fn sendsync<X: Send+Sync>(_: &X) {}
+   sendsync(&s);
+}
+
+#[test]
+fn exactkeytest() {
+   use sets::Exact;
+   let big = 1_u64 << 60;
+   let v = [big+3, big+1, big+2, 5];
+   let mut s = Set::new_unordered(&v);
+   s.mordered_by(Exact(|&x:&u64| x),true);
+   assert_eq!(s.data,vec![5,big+1,big+2,big+3]);
+   assert_eq!(s.search(big+2),Some(2));
+   let mut si = Set::new_unordered(&v);
+   si.mindexed_by(Exact(|&x:&u64| x),false);
+   assert_eq!(si.index,vec![0,2,1,3]);
+   assert!(si.member(big+1));
+   let mut sf = Set::new_unordered(&[3.,1.,2.]);
+   sf.mordered(|x| *x,false); // unannotated f64 quantify closures still work
+   assert_eq!(sf.data,vec![3.,2.,1.]);
+}
+
+#[test]
+fn radixtest() {
+   use sets::SType;
+   let mut state = 12345_u64;
+   let mut rnd = || { state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407); state };
+   let vi: Vec<i64> = (0..1000).map(|_| (rnd() >> 40) as i64 - (1 << 23)).collect();
+   let vf: Vec<f64> = vi.iter().map(|&x| x as f64 / 7.0).collect();
+   for asc in [true,false] {
+      assert_eq!(Set::new_ordered_radix(&vi,asc).data,Set::new_ordered(&vi,asc).data);
+      assert_eq!(Set::new_ordered_radix(&vf,asc).data,Set::new_ordered(&vf,asc).data);
+      let si = Set::new_indexed_radix(&vf,asc);
+      assert_eq!(si.to_ordered(asc).data,Set::new_ordered(&vf,asc).data);
+      let sr = Set::new_auto(SType::Ranked,&vi,asc);
+      assert_eq!(sr.to_ordered(asc).data,Set::new_ordered(&vi,asc).data);
+   }
+   let small = Set::new_auto(SType::Indexed,&[3_u8,1,2],true);
+   assert_eq!(small.index,vec![1,2,0]);
+   assert_eq!(Set::new_ranked_radix(&[-0.5_f32,2.,-3.],true).index,vec![1,2,0]);
+   let zeros = [0.0,-0.0,1.0];
+   for asc in [true,false] {
+      assert_eq!(Set::new_indexed_radix(&zeros,asc).index,Set::new_indexed(&zeros,asc).index);
+   }
+}
+
+#[test]
+fn strategytest() {
+   use sets::{SType,strategy::SortStrategy};
+   let v = vec![1.,14.,2.,13.,3.,12.,4.,11.,5.,10.,10.,6.,9.,7.,8.,16.];
+   let strategies = [ SortStrategy::Merge, SortStrategy::Hash(|&x| x), SortStrategy::Stable,
+      SortStrategy::Unstable, SortStrategy::Radix(|x:&f64| sets::radix::RadixKey::radix(x)),
+      SortStrategy::Insertion, SortStrategy::Adaptive ];
+   let ordered = Set::new_ordered(&v,false);
+   for strategy in strategies {
+      assert_eq!(Set::new_with(SType::Ordered,&v,false,strategy).data,ordered.data);
+      let si = Set::new_unordered(&v).to_indexed_with(true,strategy);
+      assert_eq!(si.to_ordered(false).data,ordered.data);
+      let sr = Set::new_with(SType::Ranked,&v,false,strategy);
+      assert_eq!(sr.to_ordered(false).data,ordered.data);
+      let mut sm = Set::new_unordered(&v);
+      sm.mordered_by(strategy,false);
+      assert_eq!(sm.data,ordered.data);
+   }
+}
+
+#[test]
+fn adaptivetest() {
+   use sets::{SType,strategy::adaptive_sortindex};
+   let up: Vec<u32> = (0..100).collect();
+   let down: Vec<u32> = (0..100).rev().collect();
+   assert!(Set::new_unordered(&up).is_sorted(true) && !Set::new_unordered(&up).is_sorted(false));
+   assert!(Set::new_ordered(&up,false).is_sorted(false));
+   assert_eq!(Set::new_adaptive(SType::Indexed,&down,false).index,trivindex(true,100));
+   assert_eq!(Set::new_adaptive(SType::Ranked,&down,true).index,trivindex(false,100));
+   assert_eq!(Set::new_adaptive(SType::Ordered,&down,true).data,up);
+   // sorted data with ties get the same index as from Set::new
+   for d in [[3,2,2,1],[1,2,2,3],[2,2,2,2]] {
+      for asc in [true,false] {
+         for stype in [SType::Ordered,SType::Indexed,SType::Ranked] {
+            assert_eq!(Set::new_adaptive(stype,&d,asc).to_string(),Set::new(stype,&d,asc).to_string());
+         }
+      }
+   }
+   let mut logs: Vec<u32> = (0..50).chain(20..70).chain((0..30).rev()).collect();
+   let idx = adaptive_sortindex(&logs);
+   assert!(idx.windows(2).all(|w| logs[w[0]] < logs[w[1]] || (logs[w[0]] == logs[w[1]] && w[0] < w[1])));
+   let s = Set::new_adaptive(SType::Ordered,&logs,false);
+   logs.sort_unstable_by(|a,b| b.cmp(a));
+   assert_eq!(s.data,logs);
+}
+
+#[test]
+fn galloptest() {
+   use sets::SType;
+   let big: Vec<u32> = (0..1000).map(|x| 3*x).collect();
+   let small = [999_u32,3,4,2997,3,600];
+   for stype in [SType::Unordered,SType::Ordered,SType::Indexed,SType::Ranked] {
+      let sbig = Set::new(stype,&big,false);
+      let ssmall = Set::new(stype,&small,true);
+      let merged = sbig.to_ordered(true).data.intersect(&ssmall.to_ordered(true).data);
+      assert_eq!(ssmall.intersection(&sbig).to_ordered(true).data,merged);
+      assert_eq!(sbig.intersection(&ssmall).to_ordered(true).data,vec![3,600,999,2997]);
+      assert!(!ssmall.is_subset(&sbig) && !ssmall.is_disjoint(&sbig));
+      assert!(Set::new(stype,&[2997_u32,0,3,3],true).is_subset(&sbig));
+      assert!(sbig.is_superset(&Set::new(stype,&[300_u32],true)));
+      assert!(Set::new(stype,&[1_u32,2,3001],false).is_disjoint(&sbig));
+      let union = sbig.to_ordered(true).data.merge(&ssmall.to_ordered(true).data);
+      assert_eq!(ssmall.view().union(&sbig.view()).to_ordered(true).data,union);
+      assert_eq!(sbig.view().union(&ssmall.view()).to_ordered(true).data,union);
+   }
+}
+
+#[test]
+fn simdtest() {
+   use sets::{SType,simd::{kernel,Kernel,SortedOps}};
+   println!("SIMD kernel: {}",kernel().yl());
+   let a: Vec<u32> = (0..500_u32).map(|x| x*x % 997).collect::<Vec<u32>>().sortm(true);
+   let b: Vec<u32> = (0..300_u32).map(|x| (7*x % 1009).wrapping_add(u32::MAX-600)).collect::<Vec<u32>>().sortm(true);
+   let long: Vec<u32> = (0..5000_u32).map(|x| x*x % 4999).collect::<Vec<u32>>().sortm(true);
+   let long64: Vec<u64> = long.iter().map(|&x| (x as u64) << 33).collect();
+   // every kernel available here (sse2 too on avx2 hosts), on operands of similar and of skewed lengths
+   for k in [Kernel::Scalar,Kernel::Sse2,Kernel::Avx2,Kernel::Neon].into_iter().filter(|k| k.is_available()) {
+      for (x,y) in [(&a,&b),(&a,&long),(&long,&a),(&b,&long),(&long,&b)] {
+         assert_eq!(u32::sorted_intersect_with(k,x,y),x.intersect(y));
+         assert_eq!(u32::sorted_union_with(k,x,y),x.merge(y));
+         assert_eq!(u32::sorted_difference_with(k,x,y),x.diff(y));
+         let (x64,y64): (Vec<u64>,Vec<u64>) = (x.iter().map(|&v| (v as u64) << 33).collect(),y.iter().map(|&v| (v as u64) << 33).collect());
+         assert_eq!(u64::sorted_intersect_with(k,&x64,&y64),x64.intersect(&y64));
+         assert_eq!(u64::sorted_union_with(k,&x64,&y64),x64.merge(&y64));
+         assert_eq!(u64::sorted_difference_with(k,&x64,&y64),x64.diff(&y64));
+      }
+      // similar lengths with repeats, intersected block by block
+      let reps: Vec<u32> = (0..400_u32).map(|x| x/5).collect();
+      let reps2: Vec<u32> = (0..350_u32).map(|x| x/3 + x%2).collect::<Vec<u32>>().sortm(true);
+      for (x,y) in [(&reps,&reps2),(&reps2,&reps),(&reps,&b),(&reps,&reps)] {
+         assert_eq!(u32::sorted_intersect_with(k,x,y),x.intersect(y));
+         let (x64,y64): (Vec<u64>,Vec<u64>) = (x.iter().map(|&v| v as u64).collect(),y.iter().map(|&v| v as u64).collect());
+         assert_eq!(u64::sorted_intersect_with(k,&x64,&y64),x64.intersect(&y64));
+      }
+      let big = [u32::MAX-1,u32::MAX,u32::MAX,u32::MAX,u32::MAX,u32::MAX,u32::MAX,u32::MAX,u32::MAX];
+      assert_eq!(u32::sorted_intersect_with(k,&[0,u32::MAX,u32::MAX],&big),vec![u32::MAX,u32::MAX]);
+      assert_eq!(u32::sorted_difference_with(k,&big,&[u32::MAX]),big[..8].to_vec());
+   }
+   assert!(u32::sorted_union(&a,&long) == a.merge(&long) && Kernel::best().name() == kernel());
+   for stype in [SType::Unordered,SType::Ordered,SType::Indexed,SType::Ranked] {
+      let (sa,sb) = (Set::new(stype,&long64,false),Set::new(stype,&[5_u64<<33,7<<33,9<<33],true));
+      assert_eq!(sa.intersection(&sb).to_ordered(true).data,long64.intersect(&sb.to_ordered(true).data));
+      assert_eq!(sb.union(&sa).to_ordered(true).data,long64.merge(&sb.to_ordered(true).data));
+      assert_eq!(sa.difference(&sb).to_ordered(true).data,long64.diff(&sb.to_ordered(true).data));
+      // u32 Sets of similar lengths run the kernels, with the results of the generic operations
+      let (sa,sb) = (Set::new(stype,&a,true),Set::new(stype,&b,false));
+      let (ia,ib) = (Set::new(stype,&a.iter().map(|&x| x as i64).collect::<Vec<i64>>(),true),Set::new(stype,&b.iter().map(|&x| x as i64).collect::<Vec<i64>>(),false));
+      let widen = |s: Set<u32>| s.data.iter().map(|&x| x as i64).collect::<Vec<i64>>();
+      assert_eq!(widen(sa.intersection(&sb)),ia.intersection(&ib).data);
+      assert_eq!(widen(sa.union(&sb)),ia.union(&ib).data);
+      assert_eq!(widen(sb.difference(&sa)),ib.difference(&ia).data);
+   }
+}
+
+#[test]
+fn viewtest() {
+   use sets::{SType,SetView};
+   let v = vec![5_i32,-3,8,8,0,12,-7,5];
+   let w = vec![8_i32,1,5,20];
+   for stype in [SType::Unordered,SType::Indexed,SType::Ranked] {
+      for asc in [true,false] {
+         let view = SetView::new(stype,&v,asc);
+         let set = Set::new(stype,&v,asc);
+         assert!(std::ptr::eq(view.data,v.as_slice())); // no copy of the data
+         assert_eq!(view.search(8),set.search(8));
+         assert!(view.member(-7) && !view.member(1));
+         assert_eq!(view.infsup().to_string(),set.infsup().to_string());
+         let wv = SetView::new_indexed(&w,true);
+         let ws = Set::new_indexed(&w,true);
+         assert_eq!(view.intersection(&wv).data,set.intersection(&ws).to_ordered(view.ascending).data);
+         assert_eq!(view.union(&wv).to_ordered(true).data,set.union(&ws).to_ordered(true).data);
+         assert_eq!(view.difference(&wv).to_ordered(true).data,set.difference(&ws).to_ordered(true).data);
+         assert_eq!(view.to_set().to_string(),set.to_string());
+      }
+   }
+   let sorted = Set::new_ordered(&v,false);
+   let view = sorted.view();
+   assert_eq!(view.iter().collect::<Vec<i32>>(),sorted.data);
+   assert_eq!(SetView::new_ranked(&v,true).iter().collect::<Vec<i32>>(),v.sortm(true));
+   assert_eq!(SetView::new_ordered(&sorted.data,false).search(0),sorted.search(0));
+   println!("{}",SetView::new_indexed(&v,true));
+}
+
+#[test]
+fn compacttest() {
+   use sets::{SType,compact::{CompactSet,IndexOverflow}};
+   let v = vec![5_i32,-3,8,8,0,12,-7,5];
+   for stype in [SType::Unordered,SType::Ordered,SType::Indexed,SType::Ranked] {
+      for asc in [true,false] {
+         let mut cs = CompactSet::<i32,u32>::new(stype,&v,asc).unwrap();
+         let mut s = Set::new(stype,&v,asc);
+         assert_eq!(cs.to_set().to_string(),s.to_string());
+         assert_eq!(cs.to_indexed(!asc).to_set().to_string(),s.to_indexed(!asc).to_string());
+         assert_eq!(cs.to_ranked(asc).to_set().to_string(),s.to_ranked(asc).to_string());
+         assert_eq!(cs.to_ranked(!asc).to_set().to_string(),s.to_ranked(!asc).to_string());
+         for x in [8,3,-7,20] {
+            assert_eq!(cs.search(x),s.search(x));
+            cs.minsert(x).unwrap();
+            s.minsert(x);
+            assert_eq!(cs.to_set().to_string(),s.to_string());
+         }
+         for x in [5,8,100,-7] {
+            assert_eq!(cs.mdelete(x),s.mdelete(x));
+            assert_eq!(cs.to_set().to_string(),s.to_string());
+         }
+      }
+   }
+   let long: Vec<u32> = (0..65537).rev().collect();
+   let err = CompactSet::<u32,u16>::new(SType::Indexed,&long,true).err();
+   assert_eq!(err,Some(IndexOverflow{ len:65537, max:65535 }));
+   println!("{}",err.unwrap());
+   let mut cs = CompactSet::<u32,u16>::new(SType::Ranked,&long[1..],true).unwrap();
+   assert!(cs.minsert(7).is_err() && cs.len() == 65536);
+   assert!(cs.mdelete(7) && cs.minsert(70000).is_ok() && cs.search(70000) == Some(65535));
+   let back: Set<u32> = cs.into();
+   assert_eq!(back.to_ordered(true).data[65535],70000);
+}
+
+#[test]
+fn capacitytest() {
+   use sets::SType;
+   for stype in [SType::Unordered,SType::Ordered,SType::Indexed,SType::Ranked] {
+      let mut s = Set::<u64>::with_capacity(stype,false,100);
+      assert!(s.data.capacity() >= 100 && s.search(5).is_none() && !s.mdelete(5));
+      for x in [5_u64,1,9,5,7] { s.minsert(x) };
+      assert_eq!(s.to_string(),Set::new(stype,&[5_u64,1,9,5,7],false).to_string());
+      let mem = s.memory_usage();
+      assert!(mem.data >= 800 && mem.data_used == 40);
+      s.reserve(1000);
+      assert!(s.data.capacity() >= 1005);
+      for x in [5_u64,1,9,5] { s.mdelete(x); };
+      s.shrink_to_fit();
+      println!("{}",s.memory_usage());
+      assert_eq!(s.memory_usage().total(),s.memory_usage().data_used+s.memory_usage().index_used);
+      s.clear();
+      assert!(s.data.capacity() >= 1 && s.data.is_empty() && s.search(7).is_none());
+      for x in [3_u64,8,3] { s.minsert(x) };
+      assert_eq!(s.to_string(),Set::new(stype,&[3_u64,8,3],false).to_string());
+   }
+}
+
+#[test]
+fn minserttest() {
+   use sets::{SType,table::Table};
+   // single item and all equal items, where the order can not be read off the end items
+   for stype in [SType::Ordered,SType::Indexed,SType::Ranked] {
+      for asc in [true,false] {
+         for start in [vec![4_i32],vec![4,4,4]] {
+            let mut s = Set::new(stype,&start,asc);
+            for x in [9,1,4] { s.minsert(x) };
+            let mut all = start.clone(); all.extend([9,1,4]);
+            assert_eq!(s.to_ordered(asc).data,Set::new_ordered(&all,asc).data);
+            assert!(all.iter().all(|&x| s.member(x)));
+         }
+      }
+   }
+   let mut t = Table::new(SType::Ordered,&[4.],vec!["four"],false);
+   t.insert(9.,"nine");
+   t.insert(1.,"one");
+   assert_eq!(t.keys.data,vec![9.,4.,1.]);
+   assert_eq!(t.rows,vec!["nine","four","one"]);
+}
+
+#[test]
+fn typedtest() {
+   use sets::typed::{UnorderedSet,OrderedSet,IndexedSet,RankedSet};
+   let v = vec![5_i32,-3,8,8,0,12,-7,5];
+   let unordered = UnorderedSet::new(&v);
+   let ordered: OrderedSet<i32> = unordered.to_ordered(false);
+   assert_eq!(ordered.select(1),Some(8));
+   assert_eq!(ordered.range(0..8),&[5,5,0]);
+   let indexed: IndexedSet<i32> = ordered.to_indexed(true);
+   assert_eq!(indexed.select(0),Some(-7));
+   assert_eq!(indexed.range(0..9).collect::<Vec<i32>>(),vec![0,5,5,8,8]);
+   assert_eq!(IndexedSet::new(&v,false).range(0..9).collect::<Vec<i32>>(),vec![8,8,5,5,0]);
+   let mut ranked: RankedSet<i32> = RankedSet::new(&v,true);
+   assert_eq!(ranked.rank(5),Some(7));
+   ranked.minsert(1);
+   assert_eq!(ranked.ranks().len(),9);
+   // lossless round trips through the dynamic Set
+   let set: Set<i32> = ranked.clone().into();
+   assert_eq!(RankedSet::try_from(set.clone()).ok().map(|r| r.into_set().to_string()),Some(set.to_string()));
+   assert!(OrderedSet::try_from(set).is_err());
+   let mut empty = IndexedSet::try_from(Set::<i32>::EMPTYSET).ok().unwrap();
+   assert!(empty.is_empty() && empty.clone().into_set().to_string() == Set::<i32>::EMPTYSET.to_string());
+   empty.minsert(3);
+   assert_eq!(empty.as_set().to_string(),Set::new_indexed(&[3],true).to_string());
+   let small = OrderedSet::new(&[8_i32,-7,100],true);
+   assert_eq!(small.intersection(indexed.as_set()).data(),&[-7,8]);
+   println!("{}",indexed.difference(small.as_set()));
+}
+
+#[test]
+fn emptytypedtest() {
+   use sets::{SType,typed::{UnorderedSet,OrderedSet,IndexedSet,RankedSet}};
+   let u = UnorderedSet::<i32>::new(&[]);
+   for asc in [true,false] {
+      assert!(u.to_ordered(asc).is_empty() && u.to_indexed(asc).is_empty() && u.to_ranked(asc).is_empty());
+      let o: OrderedSet<i32> = OrderedSet::new(&[],asc);
+      let i: IndexedSet<i32> = o.to_indexed(!asc);
+      let r: RankedSet<i32> = i.to_ranked(asc);
+      assert!(r.to_unordered().is_empty() && r.to_ordered(!asc).is_empty() && r.to_indexed(!asc).is_empty());
+      assert!(i.to_ordered(asc).is_empty() && i.to_unordered().to_ranked(asc).is_empty() && o.to_ranked(!asc).is_empty());
+   }
+   // the same, on typed-but-empty dynamic sets, also through the mutable converters
+   for stype in [SType::Unordered,SType::Ordered,SType::Indexed,SType::Ranked] {
+      let s = Set::<i32>::with_capacity(stype,false,4);
+      for t in [s.to_unordered(),s.to_ordered(true),s.to_indexed(true),s.to_ranked(false)] {
+         assert!(t.data.is_empty() && t.index.is_empty());
+      }
+      let mut m = s.clone();
+      m.mindexed(|x| *x as f64,true);
+      m.mranked(false);
+      m.mordered(|x| *x as f64,false);
+      m.munordered();
+      m.mranked(true);
+      assert!(m.data.is_empty() && m.index.is_empty() && matches!(m.stype,SType::Ranked));
+      // searches and deletions see no data, whatever the stype
+      assert!(matches!(m.view().stype,SType::Empty) && m.search(3).is_none() && m.infsup().max == 0);
+      assert!(!m.mdelete(3) && m.mdeleteall(3) == 0 && matches!(m.stype,SType::Ranked));
+   }
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn setliketest() {
+   use sets::setlike::{SetLike,SortedSlice};
+   use std::collections::{BTreeSet,HashSet};
+   let set = Set::new_ranked(&[5_i32,-3,8,8,0],false);
+   let btree: BTreeSet<i32> = [8,1,-3].into_iter().collect();
+   let hash: HashSet<i32> = [0,1,2].into_iter().collect();
+   let sorted = SortedSlice::new(&[-3,-3,2,9]).unwrap();
+   assert!(SortedSlice::new(&[2,1]).is_none());
+   assert_eq!(SetLike::len(&set),5);
+   assert!(SetLike::contains(&sorted,&2) && !SetLike::contains(&sorted,&3));
+   assert_eq!(set.items(),vec![8,8,5,0,-3]);
+   assert_eq!(set.union_into::<Vec<i32>>(&btree),vec![-3,0,1,5,8]);
+   assert_eq!(hash.intersection_into::<BTreeSet<i32>>(&btree),[1].into_iter().collect());
+   assert_eq!(sorted.difference_into::<HashSet<i32>>(&hash),[-3,9].into_iter().collect());
+   let (ranked,unordered) = (Set::new_ranked(&[9,2,7,2,4],true),Set::new_unordered(&[4,8,2,4,0]));
+   assert_eq!(ranked.intersection_into::<Vec<i32>>(&unordered),vec![2,4]);
+   assert_eq!(ranked.difference_into::<Vec<i32>>(&unordered),vec![7,9]);
+   assert_eq!(unordered.difference_into::<Vec<i32>>(&ranked),vec![0,8]);
+   let setout: Set<i32> = btree.union_into(&sorted);
+   assert_eq!(setout.to_string(),Set::new_ordered(&[-3,1,2,8,9],true).to_string());
+   // conversions with the std collections
+   assert_eq!(Set::from(btree.clone()).to_string(),Set::new_ordered(&[-3,1,8],true).to_string());
+   assert_eq!(Set::from(hash).to_ordered(true).data,vec![0,1,2]);
+   assert_eq!(Set::from(sorted).data,vec![-3,-3,2,9]);
+   assert_eq!(BTreeSet::from(set.clone()),[-3,0,5,8].into_iter().collect());
+   assert_eq!(HashSet::from(set).len(),4);
+}
+
+static KEYWORDS: sets::SetView<&str> = sets::set![view asc; "as", "break", "const", "fn", "let", "mut", "static"];
+
+#[test]
+fn literaltest() {
+   use sets::{set,SType,SetView};
+   let empty: Set<u8> = set![];
+   assert!(matches!(empty.stype,SType::Empty));
+   assert_eq!(set![3,1,2].to_string(),Set::new_unordered(&[3,1,2]).to_string());
+   assert_eq!(set![unordered; 3,1,2,].data,vec![3,1,2]);
+   assert_eq!(set![ordered; 3,1,2].data,vec![1,2,3]);
+   assert_eq!(set![ordered desc; 3,1,2].data,vec![3,2,1]);
+   assert_eq!(set![indexed desc; 3.5,1.,2.].to_string(),Set::new_indexed(&[3.5,1.,2.],false).to_string());
+   assert_eq!(set![ranked asc; 'c','a','b'].index.to_vec(),vec![2,0,1]);
+   let none: Set<i32> = set![ranked desc;];
+   assert!(matches!(none.stype,SType::Empty));
+   // const constructed lookup table
+   const DIGITS: SetView<u8> = SetView::new_sorted(b"9876543210", false);
+   assert!(DIGITS.member(b'5') && !DIGITS.member(b'a'));
+   assert!(KEYWORDS.member("fn") && !KEYWORDS.member("loop"));
+   assert_eq!(KEYWORDS.search("let"),Some(4));
+   assert_eq!(KEYWORDS.to_set().to_string(),Set::new_ordered(KEYWORDS.data,true).to_string());
+}
+
+#[test]
+fn parsetest() {
+   use sets::{SType,ParseSetError};
+   let s: Set<i32> = "{3, 1, 2}".parse().unwrap();
+   assert_eq!(s.to_string(),Set::new_unordered(&[3,1,2]).to_string());
+   assert_eq!("[1..5, 8]".parse::<Set<u8>>().unwrap().data,vec![1,2,3,4,8]);
+   assert_eq!(" 7 -2,-4..=-3 ".parse::<Set<i64>>().unwrap().data,vec![7,-2,-4,-3]);
+   assert_eq!("0.5, 2e1 1..3".parse::<Set<f64>>().unwrap().data,vec![0.5,20.,1.,2.]);
+   assert!(matches!("{}".parse::<Set<i32>>().unwrap().stype,SType::Empty));
+   let r = Set::<i32>::parse_as(SType::Ranked,"{5, 1..4,}",false).unwrap();
+   assert_eq!(r.to_string(),Set::new_ranked(&[5,1,2,3],false).to_string());
+   assert_eq!(Set::<i32>::parse_as(SType::Ordered,"9 0..3",true).unwrap().data,vec![0,1,2,9]);
+   // errors locate the offending token
+   let err = |s: &str| s.parse::<Set<u8>>().err().unwrap();
+   assert_eq!(err("{1, x2, 3}"),ParseSetError{ position:4, token:"x2".to_string(), reason:"invalid item" });
+   assert_eq!(err("[1,,2]").position,3);
+   assert_eq!(err("{1, 2").reason,"unclosed bracket");
+   assert_eq!(err("1 2]").position,3);
+   assert_eq!(err("250..300").reason,"range item out of type");
+   assert_eq!(err("5..5").reason,"empty range");
+   assert_eq!(err("1..b").token,"1..b");
+   assert_eq!(err("0..-170141183460469231731687303715884105728").reason,"empty range");
+   assert_eq!(err("-170141183460469231731687303715884105728..=170141183460469231731687303715884105727").reason,"range longer than MAX_RANGE_LEN");
+   assert_eq!("0..10000000000000".parse::<Set<u64>>().err().map(|e| e.reason),Some("range longer than MAX_RANGE_LEN"));
+   assert_eq!("0..1048576".parse::<Set<u64>>().map(|s| s.data.len()),Ok(1<<20));
+   // the four ranges make up MAX_ITEMS, one more item is too many
+   let four = "0..1048576, ".repeat(4);
+   assert_eq!((four.clone() + "7").parse::<Set<u32>>().err().map(|e| (e.reason,e.position)),Some(("more than MAX_ITEMS items",48)));
+   assert_eq!((four + "0..=0").parse::<Set<u32>>().err().map(|e| e.reason),Some("more than MAX_ITEMS items"));
+   println!("{}",err("{1, 2, three}"));
+}
+
+#[test]
+fn compattest() {
+   use sets::nostd;
+   use std::panic::catch_unwind;
+   // the alloc only replacements give the same results as indxvec, on the same inputs
+   let inputs: [&[f64];8] = [ &[1.,14.,2.,13.,3.,12.,4.,11.,5.,10.,10.,6.,9.,7.,8.,16.], &[7.], &[3.,3.,3.],
+      &[1.,2.,2.,5.,9.,9.], &[9.,9.,5.,2.,2.,1.], &[-0.5,4.,4.,-2.,0.], &[2.,2.,2.,2.,2.,2.,2.],
+      &[3.,1.,3.,1.,2.,3.,1.,2.,2.,3.,1.] ];
+   for d in inputs {
+      let asc = Vecops::sortm(d,true);
+      for sorted in [&asc,&Vecops::sortm(d,false)] {
+         for x in [-3.,1.,2.,3.,4.,7.,9.,20.] {
+            assert_eq!(nostd::Vecops::binsearch(&sorted[..],&x),Vecops::binsearch(&sorted[..],&x),"binsearch {x} in {sorted:?}");
+         }
+      }
+      let idx = Vecops::mergesort_indexed(d);
+      assert_eq!(nostd::Vecops::mergesort_indexed(d),idx);
+      assert_eq!(nostd::Vecops::hashsort_indexed(d,|&x| x),Vecops::hashsort_indexed(d,|&x| x));
+      for x in [-3.,1.,4.,7.,9.] {
+         assert_eq!(nostd::Vecops::binsearch_indexed(d,&idx,&x),Vecops::binsearch_indexed(d,&idx,&x));
+         assert_eq!(nostd::Vecops::member(d,x,true),Vecops::member(d,x,true));
+         assert_eq!(nostd::Vecops::member(d,x,false),Vecops::member(d,x,false));
+      }
+      let (m1,m2) = (nostd::Vecops::minmax(d),Vecops::minmax(d));
+      assert_eq!((m1.min,m1.minindex,m1.max,m1.maxindex),(m2.min,m2.minindex,m2.max,m2.maxindex));
+      assert_eq!(nostd::Vecops::revs(d),Vecops::revs(d));
+      for other in inputs {
+         let other = Vecops::sortm(other,true);
+         assert_eq!(nostd::Vecops::merge(&asc[..],&other),Vecops::merge(&asc[..],&other));
+         assert_eq!(nostd::Vecops::intersect(&asc[..],&other),Vecops::intersect(&asc[..],&other));
+         assert_eq!(nostd::Vecops::diff(&asc[..],&other),Vecops::diff(&asc[..],&other));
+      }
+      assert_eq!(nostd::Indices::invindex(&idx[..]),Indices::invindex(&idx[..]));
+      assert_eq!(nostd::Indices::complindex(&idx[..]),Indices::complindex(&idx[..]));
+      for up in [true,false] { assert_eq!(nostd::Indices::unindex(&idx[..],d,up),Indices::unindex(&idx[..],d,up)) };
+      let (mut r1,mut r2) = (d.to_vec(),d.to_vec());
+      nostd::Mutops::mutrevs(&mut r1[..]);
+      indxvec::Mutops::mutrevs(&mut r2[..]);
+      assert_eq!(r1,r2);
+      assert_eq!(nostd::Printing::to_str(d),Printing::to_str(d));
+   }
+   let empty: &[f64] = &[];
+   assert_eq!(nostd::Vecops::merge(empty,&[1.]),Vecops::merge(empty,&[1.]));
+   assert_eq!(nostd::Printing::to_str(empty),Printing::to_str(empty));
+   // both panic on searching no data
+   assert!(catch_unwind(|| nostd::Vecops::binsearch(empty,&1.)).is_err());
+   assert!(catch_unwind(|| Vecops::binsearch(empty,&1.)).is_err());
+}